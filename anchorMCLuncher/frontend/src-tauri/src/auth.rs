@@ -1,73 +1,167 @@
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use std::error::Error;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::{AppHandle, State};
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Agent {
-    pub name: String,
-    pub version: u8,
-}
+use crate::launcher::MinecraftAccount;
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct AuthRequest {
-    pub agent: Agent,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthSession {
+    pub provider: String,
     pub username: String,
-    pub password: String,
-    #[serde(rename = "clientToken")]
+    pub uuid: String,
+    pub access_token: String,
     pub client_token: Option<String>,
-    #[serde(rename = "requestUser")]
-    pub request_user: bool,
+    pub refresh_token: Option<String>,
+    pub auth_server: Option<String>,
+    pub xuid: Option<String>,
+    pub client_id: Option<String>,
+    pub user_properties: Option<serde_json::Value>,
+    pub owns_game: Option<bool>,
+    // The user type the caller gave the account, passed through to ${user_type}; None lets the provider pick
+    #[serde(default)]
+    pub user_type: Option<String>,
+}
+
+impl AuthSession {
+    // Sessions built from the loose account/auth_server launch parameters the UI used to send
+    pub fn from_legacy(account: Option<MinecraftAccount>, auth_server: Option<String>) -> Self {
+        let account = account.unwrap_or_else(MinecraftAccount::offline);
+        let provider = if matches!(account.user_type.as_str(), "msa" | "microsoft") {
+            "microsoft"
+        } else if auth_server.is_some() {
+            "yggdrasil"
+        } else {
+            "offline"
+        };
+
+        Self {
+            provider: provider.to_string(),
+            username: account.username,
+            uuid: account.uuid,
+            access_token: account.access_token,
+            client_token: None,
+            refresh_token: None,
+            auth_server,
+            xuid: account.xuid,
+            client_id: account.client_id,
+            user_properties: account.user_properties,
+            owns_game: account.owns_game,
+            user_type: Some(account.user_type),
+        }
+    }
+
+    // `user_type` is only used when the session doesn't carry one of its own
+    pub fn to_account(&self, user_type: &str) -> MinecraftAccount {
+        MinecraftAccount {
+            username: self.username.clone(),
+            uuid: self.uuid.clone(),
+            access_token: self.access_token.clone(),
+            user_type: self.user_type.clone().unwrap_or_else(|| user_type.to_string()),
+            xuid: self.xuid.clone(),
+            client_id: self.client_id.clone(),
+            user_properties: self.user_properties.clone(),
+            owns_game: self.owns_game,
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Profile {
-    pub id: String,
-    pub name: String,
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LoginRequest {
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub auth_server: Option<String>,
+    // OAuth authorization code, for providers that log in through a browser
+    pub code: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct AuthResponse {
-    #[serde(rename = "accessToken")]
-    pub access_token: String,
-    #[serde(rename = "clientToken")]
-    pub client_token: Option<String>,
-    #[serde(rename = "selectedProfile")]
-    pub selected_profile: Option<Profile>,
-    #[serde(rename = "availableProfiles")]
-    pub available_profiles: Option<Vec<Profile>>,
+pub struct LaunchContext {
+    pub app: AppHandle,
+    pub mc_dir: PathBuf,
+    pub version_dir: PathBuf,
+}
+
+pub struct LaunchAuth {
+    pub jvm_args: Vec<String>,
+    pub account: MinecraftAccount,
+}
+
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    fn id(&self) -> &'static str;
+
+    async fn login(&self, app: &AppHandle, request: LoginRequest) -> Result<AuthSession, String>;
+
+    async fn refresh(&self, app: &AppHandle, session: &AuthSession) -> Result<AuthSession, String>;
+
+    async fn validate(&self, app: &AppHandle, session: &AuthSession) -> Result<bool, String>;
+
+    async fn logout(&self, app: &AppHandle, session: &AuthSession) -> Result<(), String>;
+
+    async fn launch_arguments(&self, ctx: &LaunchContext, session: &AuthSession) -> Result<LaunchAuth, String>;
 }
 
-pub async fn authenticate(
-    auth_server_url: &str,
-    username: &str,
-    password: &str,
-) -> Result<AuthResponse, Box<dyn Error>> {
-    let client = reqwest::Client::new();
-    let url = format!("{}/authserver/authenticate", auth_server_url.trim_end_matches('/'));
-
-    let payload = AuthRequest {
-        agent: Agent {
-            name: "Minecraft".to_string(),
-            version: 1,
-        },
-        username: username.to_string(),
-        password: password.to_string(),
-        client_token: None, // You might want to generate a UUID here
-        request_user: true,
-    };
-
-    let resp = client.post(&url)
-        .json(&payload)
-        .send()
-        .await?;
-
-    if resp.status().is_success() {
-        let response_text = resp.text().await?;
-        println!("Raw Auth Response: {}", response_text);
-        let auth_response: AuthResponse = serde_json::from_str(&response_text)?;
-        Ok(auth_response)
-    } else {
-        let status = resp.status();
-        let error_text = resp.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        Err(format!("Authentication failed (Status {}): {}", status, error_text).into())
+pub struct AuthRegistry {
+    providers: HashMap<&'static str, Arc<dyn AuthProvider>>,
+}
+
+impl AuthRegistry {
+    pub fn new() -> Self {
+        let mut registry = Self {
+            providers: HashMap::new(),
+        };
+        registry.register(Arc::new(crate::auth_microsoft::MicrosoftProvider));
+        registry.register(Arc::new(crate::auth_yggdrasil::YggdrasilProvider));
+        registry.register(Arc::new(crate::auth_offline::OfflineProvider));
+        registry
     }
+
+    pub fn register(&mut self, provider: Arc<dyn AuthProvider>) {
+        self.providers.insert(provider.id(), provider);
+    }
+
+    pub fn get(&self, id: &str) -> Result<Arc<dyn AuthProvider>, String> {
+        self.providers
+            .get(id)
+            .cloned()
+            .ok_or_else(|| format!("Unknown auth provider: {}", id))
+    }
+
+    pub fn ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.providers.keys().map(|id| id.to_string()).collect();
+        ids.sort();
+        ids
+    }
+}
+
+#[tauri::command]
+pub fn list_auth_providers(registry: State<'_, AuthRegistry>) -> Vec<String> {
+    registry.ids()
+}
+
+#[tauri::command]
+pub async fn login(
+    app: AppHandle,
+    registry: State<'_, AuthRegistry>,
+    provider: String,
+    request: LoginRequest
+) -> Result<AuthSession, String> {
+    registry.get(&provider)?.login(&app, request).await
+}
+
+#[tauri::command]
+pub async fn refresh_session(app: AppHandle, registry: State<'_, AuthRegistry>, session: AuthSession) -> Result<AuthSession, String> {
+    registry.get(&session.provider)?.refresh(&app, &session).await
+}
+
+#[tauri::command]
+pub async fn validate_session(app: AppHandle, registry: State<'_, AuthRegistry>, session: AuthSession) -> Result<bool, String> {
+    registry.get(&session.provider)?.validate(&app, &session).await
+}
+
+#[tauri::command]
+pub async fn logout(app: AppHandle, registry: State<'_, AuthRegistry>, session: AuthSession) -> Result<(), String> {
+    registry.get(&session.provider)?.logout(&app, &session).await
 }
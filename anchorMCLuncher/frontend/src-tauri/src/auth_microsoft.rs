@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+use base64::Engine;
+use serde::Deserialize;
+use tauri::AppHandle;
+
+use crate::auth::{AuthProvider, AuthSession, LaunchAuth, LaunchContext, LoginRequest};
+
+const CLIENT_ID: &str = "00000000402b5328";
+const REDIRECT_URI: &str = "https://login.live.com/oauth20_desktop.srf";
+const SCOPE: &str = "service::user.auth.xboxlive.com::MBI_SSL";
+const AUTHORIZE_URL: &str = "https://login.live.com/oauth20_authorize.srf";
+const TOKEN_URL: &str = "https://login.live.com/oauth20_token.srf";
+const XBL_URL: &str = "https://user.auth.xboxlive.com/user/authenticate";
+const XSTS_URL: &str = "https://xsts.auth.xboxlive.com/xsts/authorize";
+const MINECRAFT_SERVICES_API: &str = "https://api.minecraftservices.com";
+
+#[derive(Debug, Deserialize)]
+struct MsaToken {
+    access_token: String,
+    refresh_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct XboxToken {
+    token: String,
+    display_claims: XboxDisplayClaims,
+}
+
+#[derive(Debug, Deserialize)]
+struct XboxDisplayClaims {
+    xui: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MinecraftToken {
+    access_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Entitlements {
+    #[serde(default)]
+    items: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MinecraftProfile {
+    id: String,
+    name: String,
+}
+
+pub struct MicrosoftProvider;
+
+impl MicrosoftProvider {
+    async fn exchange_token(client: &reqwest::Client, params: &[(&str, &str)]) -> Result<MsaToken, String> {
+        let resp = client.post(TOKEN_URL).form(params).send().await.map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let detail = resp.text().await.unwrap_or_default();
+            return Err(format!("Microsoft login failed (Status {}): {}", status, detail));
+        }
+        resp.json().await.map_err(|e| e.to_string())
+    }
+
+    async fn xbox_authorize(client: &reqwest::Client, url: &str, body: serde_json::Value) -> Result<XboxToken, String> {
+        let resp = client.post(url).json(&body).send().await.map_err(|e| e.to_string())?;
+        if resp.status() == reqwest::StatusCode::UNAUTHORIZED {
+            let detail: serde_json::Value = resp.json().await.unwrap_or_default();
+            return Err(match detail["XErr"].as_u64() {
+                Some(2148916233) => "This Microsoft account has no Xbox profile".to_string(),
+                Some(2148916238) => "This Microsoft account belongs to a child and must be added to a family".to_string(),
+                _ => format!("Xbox Live authorization failed: {}", detail),
+            });
+        }
+        if !resp.status().is_success() {
+            return Err(format!("Xbox Live authorization failed: {}", resp.status()));
+        }
+        resp.json().await.map_err(|e| e.to_string())
+    }
+
+    // The Minecraft access token is a JWT whose payload carries the Xbox user id
+    fn xuid_from_token(token: &str) -> Option<String> {
+        let payload = token.split('.').nth(1)?;
+        let decoded = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok()?;
+        let claims: serde_json::Value = serde_json::from_slice(&decoded).ok()?;
+        claims["xuid"].as_str().map(|s| s.to_string())
+    }
+
+    // Xbox Live -> XSTS -> Minecraft services, then ownership and profile lookup
+    async fn complete_login(client: &reqwest::Client, msa: MsaToken) -> Result<AuthSession, String> {
+        let xbl = Self::xbox_authorize(client, XBL_URL, serde_json::json!({
+            "Properties": {
+                "AuthMethod": "RPS",
+                "SiteName": "user.auth.xboxlive.com",
+                "RpsTicket": msa.access_token
+            },
+            "RelyingParty": "http://auth.xboxlive.com",
+            "TokenType": "JWT"
+        })).await?;
+        let uhs = xbl.display_claims.xui.first()
+            .and_then(|x| x["uhs"].as_str())
+            .ok_or("Xbox Live response has no user hash")?
+            .to_string();
+
+        let xsts = Self::xbox_authorize(client, XSTS_URL, serde_json::json!({
+            "Properties": {
+                "SandboxId": "RETAIL",
+                "UserTokens": [xbl.token]
+            },
+            "RelyingParty": "rp://api.minecraftservices.com/",
+            "TokenType": "JWT"
+        })).await?;
+
+        let resp = client
+            .post(format!("{}/authentication/login_with_xbox", MINECRAFT_SERVICES_API))
+            .json(&serde_json::json!({ "identityToken": format!("XBL3.0 x={};{}", uhs, xsts.token) }))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            return Err(format!("Minecraft services login failed: {}", resp.status()));
+        }
+        let mc: MinecraftToken = resp.json().await.map_err(|e| e.to_string())?;
+
+        let resp = client
+            .get(format!("{}/entitlements/mcstore", MINECRAFT_SERVICES_API))
+            .bearer_auth(&mc.access_token)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        // Only an actual empty list means demo mode; a failed check must not take the game away from an owner
+        if !resp.status().is_success() {
+            return Err(format!("Failed to check game ownership: {}", resp.status()));
+        }
+        let entitlements: Entitlements = resp.json().await.map_err(|e| e.to_string())?;
+        let owns_game = !entitlements.items.is_empty();
+
+        let resp = client
+            .get(format!("{}/minecraft/profile", MINECRAFT_SERVICES_API))
+            .bearer_auth(&mc.access_token)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        // Accounts without the game have no profile; they can still play the demo
+        let profile = if resp.status().is_success() {
+            resp.json::<MinecraftProfile>().await.map_err(|e| e.to_string())?
+        } else if !owns_game {
+            MinecraftProfile {
+                id: crate::auth_offline::offline_uuid("Player"),
+                name: "Player".to_string(),
+            }
+        } else {
+            return Err(format!("Failed to fetch Minecraft profile: {}", resp.status()));
+        };
+
+        Ok(AuthSession {
+            provider: "microsoft".to_string(),
+            username: profile.name,
+            uuid: profile.id,
+            xuid: Self::xuid_from_token(&mc.access_token),
+            access_token: mc.access_token,
+            client_token: None,
+            refresh_token: msa.refresh_token,
+            auth_server: None,
+            client_id: Some(CLIENT_ID.to_string()),
+            user_properties: None,
+            owns_game: Some(owns_game),
+            user_type: None,
+        })
+    }
+}
+
+#[async_trait]
+impl AuthProvider for MicrosoftProvider {
+    fn id(&self) -> &'static str {
+        "microsoft"
+    }
+
+    async fn login(&self, _app: &AppHandle, request: LoginRequest) -> Result<AuthSession, String> {
+        let code = request.code.ok_or("Microsoft login requires an authorization code")?;
+        let client = reqwest::Client::new();
+        let msa = Self::exchange_token(&client, &[
+            ("client_id", CLIENT_ID),
+            ("code", &code),
+            ("grant_type", "authorization_code"),
+            ("redirect_uri", REDIRECT_URI),
+            ("scope", SCOPE),
+        ]).await?;
+        Self::complete_login(&client, msa).await
+    }
+
+    async fn refresh(&self, _app: &AppHandle, session: &AuthSession) -> Result<AuthSession, String> {
+        let refresh_token = session.refresh_token.as_deref().ok_or("Session has no refresh token")?;
+        let client = reqwest::Client::new();
+        let msa = Self::exchange_token(&client, &[
+            ("client_id", CLIENT_ID),
+            ("refresh_token", refresh_token),
+            ("grant_type", "refresh_token"),
+            ("redirect_uri", REDIRECT_URI),
+            ("scope", SCOPE),
+        ]).await?;
+        Self::complete_login(&client, msa).await
+    }
+
+    async fn validate(&self, _app: &AppHandle, session: &AuthSession) -> Result<bool, String> {
+        let client = reqwest::Client::new();
+        let resp = client
+            .get(format!("{}/minecraft/profile", MINECRAFT_SERVICES_API))
+            .bearer_auth(&session.access_token)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        // Demo accounts have no profile, which an otherwise valid token gets back as a 404
+        let status = resp.status();
+        Ok(status.is_success() || (status == reqwest::StatusCode::NOT_FOUND && session.owns_game == Some(false)))
+    }
+
+    async fn logout(&self, _app: &AppHandle, _session: &AuthSession) -> Result<(), String> {
+        // Minecraft services tokens cannot be revoked; dropping the session is enough
+        Ok(())
+    }
+
+    async fn launch_arguments(&self, _ctx: &LaunchContext, session: &AuthSession) -> Result<LaunchAuth, String> {
+        Ok(LaunchAuth {
+            jvm_args: Vec::new(),
+            account: session.to_account("msa"),
+        })
+    }
+}
+
+#[tauri::command]
+pub fn get_microsoft_login_url() -> Result<String, String> {
+    let url = reqwest::Url::parse_with_params(
+        AUTHORIZE_URL,
+        &[("client_id", CLIENT_ID), ("response_type", "code"), ("scope", SCOPE), ("redirect_uri", REDIRECT_URI)],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(url.into())
+}
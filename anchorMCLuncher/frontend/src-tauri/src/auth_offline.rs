@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use md5::{Digest, Md5};
+use tauri::AppHandle;
+
+use crate::auth::{AuthProvider, AuthSession, LaunchAuth, LaunchContext, LoginRequest};
+
+pub struct OfflineProvider;
+
+// Same as Java's UUID.nameUUIDFromBytes("OfflinePlayer:<name>"), which offline-mode servers use
+pub fn offline_uuid(username: &str) -> String {
+    let digest: [u8; 16] = Md5::digest(format!("OfflinePlayer:{}", username).as_bytes()).into();
+    uuid::Builder::from_md5_bytes(digest).into_uuid().simple().to_string()
+}
+
+#[async_trait]
+impl AuthProvider for OfflineProvider {
+    fn id(&self) -> &'static str {
+        "offline"
+    }
+
+    async fn login(&self, _app: &AppHandle, request: LoginRequest) -> Result<AuthSession, String> {
+        let username = request
+            .username
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty())
+            .ok_or("Username is required")?;
+        let uuid = offline_uuid(&username);
+
+        Ok(AuthSession {
+            provider: "offline".to_string(),
+            username,
+            access_token: uuid.clone(),
+            uuid,
+            client_token: None,
+            refresh_token: None,
+            auth_server: None,
+            xuid: None,
+            client_id: None,
+            user_properties: None,
+            owns_game: None,
+            user_type: Some("legacy".to_string()),
+        })
+    }
+
+    async fn refresh(&self, _app: &AppHandle, session: &AuthSession) -> Result<AuthSession, String> {
+        Ok(session.clone())
+    }
+
+    async fn validate(&self, _app: &AppHandle, _session: &AuthSession) -> Result<bool, String> {
+        Ok(true)
+    }
+
+    async fn logout(&self, _app: &AppHandle, _session: &AuthSession) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn launch_arguments(&self, _ctx: &LaunchContext, session: &AuthSession) -> Result<LaunchAuth, String> {
+        Ok(LaunchAuth {
+            jvm_args: Vec::new(),
+            account: session.to_account("legacy"),
+        })
+    }
+}
@@ -0,0 +1,172 @@
+use base64::Engine;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::State;
+
+const API_LOCATION_HEADER: &str = "X-Authlib-Injector-API-Location";
+const METADATA_TTL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthServerInfo {
+    pub api_root: String,
+    pub server_name: Option<String>,
+    pub implementation_name: Option<String>,
+    pub implementation_version: Option<String>,
+    pub homepage: Option<String>,
+    pub register_url: Option<String>,
+    pub skin_domains: Vec<String>,
+    pub features: HashMap<String, bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthServerMetadata {
+    pub info: AuthServerInfo,
+    // Raw metadata body, passed to authlib-injector as-is so it doesn't refetch it
+    pub raw: String,
+    fetched_at: Instant,
+}
+
+impl AuthServerMetadata {
+    pub fn prefetched_argument(&self) -> String {
+        format!(
+            "-Dauthlibinjector.yggdrasil.prefetched={}",
+            base64::engine::general_purpose::STANDARD.encode(self.raw.as_bytes())
+        )
+    }
+}
+
+pub struct AuthServerState {
+    cache: Mutex<HashMap<String, AuthServerMetadata>>,
+}
+
+impl AuthServerState {
+    pub fn new() -> Self {
+        Self {
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get_cached(&self, key: &str) -> Option<AuthServerMetadata> {
+        let cache = self.cache.lock().ok()?;
+        cache
+            .get(key)
+            .filter(|m| m.fetched_at.elapsed() < METADATA_TTL)
+            .cloned()
+    }
+
+    fn store(&self, key: &str, metadata: AuthServerMetadata) {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.insert(key.to_string(), metadata);
+        }
+    }
+}
+
+fn normalize_url(url: &str) -> String {
+    let trimmed = url.trim();
+    if trimmed.starts_with("http://") || trimmed.starts_with("https://") {
+        trimmed.to_string()
+    } else {
+        format!("https://{}", trimmed)
+    }
+}
+
+// Follows redirects and the API Location Indication header, returning the final API root
+// together with the metadata document served there.
+async fn resolve_and_fetch(client: &reqwest::Client, url: &str) -> Result<(String, String), String> {
+    let resp = client.get(url).send().await.map_err(|e| e.to_string())?;
+    let final_url = resp.url().clone();
+
+    if let Some(location) = resp.headers().get(API_LOCATION_HEADER).and_then(|v| v.to_str().ok()) {
+        let target = final_url.join(location).map_err(|e| e.to_string())?;
+        if target != final_url {
+            let target_resp = client.get(target.clone()).send().await.map_err(|e| e.to_string())?;
+            if !target_resp.status().is_success() {
+                return Err(format!("Failed to fetch auth server metadata: {}", target_resp.status()));
+            }
+            let target_url = target_resp.url().to_string();
+            let body = target_resp.text().await.map_err(|e| e.to_string())?;
+            return Ok((target_url, body));
+        }
+    }
+
+    if !resp.status().is_success() {
+        return Err(format!("Failed to fetch auth server metadata: {}", resp.status()));
+    }
+    let body = resp.text().await.map_err(|e| e.to_string())?;
+    Ok((final_url.to_string(), body))
+}
+
+fn parse_metadata(api_root: &str, raw: &str) -> Result<AuthServerInfo, String> {
+    let json: serde_json::Value = serde_json::from_str(raw)
+        .map_err(|e| format!("Auth server returned invalid metadata: {}", e))?;
+    let meta = json.get("meta").cloned().unwrap_or(serde_json::Value::Null);
+
+    let meta_str = |key: &str| meta.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+    let link_str = |key: &str| {
+        meta.get("links")
+            .and_then(|l| l.get(key))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    };
+
+    let mut features = HashMap::new();
+    if let Some(obj) = meta.as_object() {
+        for (key, value) in obj {
+            if let (Some(name), Some(enabled)) = (key.strip_prefix("feature."), value.as_bool()) {
+                features.insert(name.to_string(), enabled);
+            }
+        }
+    }
+
+    let skin_domains = json
+        .get("skinDomains")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().filter_map(|d| d.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default();
+
+    Ok(AuthServerInfo {
+        api_root: api_root.trim_end_matches('/').to_string(),
+        server_name: meta_str("serverName"),
+        implementation_name: meta_str("implementationName"),
+        implementation_version: meta_str("implementationVersion"),
+        homepage: link_str("homepage"),
+        register_url: link_str("register"),
+        skin_domains,
+        features,
+    })
+}
+
+pub async fn get_metadata(state: &AuthServerState, auth_server: &str, refresh: bool) -> Result<AuthServerMetadata, String> {
+    let key = normalize_url(auth_server);
+    if !refresh {
+        if let Some(cached) = state.get_cached(&key) {
+            return Ok(cached);
+        }
+    }
+    if crate::http_cache::is_offline() {
+        return Err("Offline mode is on".to_string());
+    }
+
+    let client = reqwest::Client::new();
+    let (api_root, raw) = resolve_and_fetch(&client, &key).await?;
+    let info = parse_metadata(&api_root, &raw)?;
+    let metadata = AuthServerMetadata {
+        info,
+        raw,
+        fetched_at: Instant::now(),
+    };
+    state.store(&key, metadata.clone());
+    Ok(metadata)
+}
+
+#[tauri::command]
+pub async fn get_auth_server_metadata(
+    state: State<'_, AuthServerState>,
+    auth_server: String,
+    refresh: Option<bool>
+) -> Result<AuthServerInfo, String> {
+    let metadata = get_metadata(&state, &auth_server, refresh.unwrap_or(false)).await?;
+    Ok(metadata.info)
+}
@@ -0,0 +1,205 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::auth::{AuthProvider, AuthSession, LaunchAuth, LaunchContext, LoginRequest};
+use crate::auth_server::AuthServerState;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Agent {
+    pub name: String,
+    pub version: u8,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AuthRequest {
+    pub agent: Agent,
+    pub username: String,
+    pub password: String,
+    #[serde(rename = "clientToken")]
+    pub client_token: Option<String>,
+    #[serde(rename = "requestUser")]
+    pub request_user: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Profile {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct User {
+    pub id: String,
+    #[serde(default)]
+    pub properties: Vec<serde_json::Value>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AuthResponse {
+    #[serde(rename = "accessToken")]
+    pub access_token: String,
+    #[serde(rename = "clientToken")]
+    pub client_token: Option<String>,
+    #[serde(rename = "selectedProfile")]
+    pub selected_profile: Option<Profile>,
+    #[serde(rename = "availableProfiles")]
+    pub available_profiles: Option<Vec<Profile>>,
+    pub user: Option<User>,
+}
+
+pub struct YggdrasilProvider;
+
+impl YggdrasilProvider {
+    async fn api_root(app: &AppHandle, auth_server: &Option<String>) -> Result<String, String> {
+        let server = auth_server.as_ref().ok_or("Yggdrasil login requires an auth server")?;
+        let state = app.state::<AuthServerState>();
+        // Prefer the API root advertised by the server, fall back to the URL as typed
+        match crate::auth_server::get_metadata(&state, server, false).await {
+            Ok(metadata) => Ok(metadata.info.api_root),
+            Err(_) => Ok(server.trim_end_matches('/').to_string()),
+        }
+    }
+
+    async fn post(api_root: &str, endpoint: &str, payload: &serde_json::Value) -> Result<reqwest::Response, String> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/authserver/{}", api_root, endpoint);
+        client.post(&url).json(payload).send().await.map_err(|e| e.to_string())
+    }
+
+    async fn error_text(resp: reqwest::Response) -> String {
+        let status = resp.status();
+        let error_text = resp.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        format!("Authentication failed (Status {}): {}", status, error_text)
+    }
+
+    fn session_from_response(response: AuthResponse, auth_server: Option<String>, fallback: Option<&AuthSession>) -> Result<AuthSession, String> {
+        let profile = response
+            .selected_profile
+            .clone()
+            .or_else(|| fallback.map(|s| Profile { id: s.uuid.clone(), name: s.username.clone() }))
+            .or_else(|| response.available_profiles.as_ref().and_then(|p| p.first().cloned()))
+            .ok_or("Account has no game profile")?;
+
+        // Properties are passed to legacy versions through ${user_properties}
+        let user_properties = response.user.as_ref().map(|u| {
+            let mut props = serde_json::Map::new();
+            for prop in &u.properties {
+                if let (Some(name), Some(value)) = (prop["name"].as_str(), prop.get("value")) {
+                    props.insert(name.to_string(), serde_json::json!([value]));
+                }
+            }
+            serde_json::Value::Object(props)
+        });
+
+        Ok(AuthSession {
+            provider: "yggdrasil".to_string(),
+            username: profile.name,
+            uuid: profile.id,
+            access_token: response.access_token,
+            client_token: response.client_token,
+            refresh_token: None,
+            auth_server,
+            xuid: None,
+            client_id: None,
+            user_properties,
+            owns_game: None,
+            user_type: None,
+        })
+    }
+}
+
+#[async_trait]
+impl AuthProvider for YggdrasilProvider {
+    fn id(&self) -> &'static str {
+        "yggdrasil"
+    }
+
+    async fn login(&self, app: &AppHandle, request: LoginRequest) -> Result<AuthSession, String> {
+        let api_root = Self::api_root(app, &request.auth_server).await?;
+        let payload = AuthRequest {
+            agent: Agent {
+                name: "Minecraft".to_string(),
+                version: 1,
+            },
+            username: request.username.ok_or("Username is required")?,
+            password: request.password.ok_or("Password is required")?,
+            client_token: Some(uuid::Uuid::new_v4().simple().to_string()),
+            request_user: true,
+        };
+        let payload = serde_json::to_value(&payload).map_err(|e| e.to_string())?;
+
+        let resp = Self::post(&api_root, "authenticate", &payload).await?;
+        if !resp.status().is_success() {
+            return Err(Self::error_text(resp).await);
+        }
+        let response: AuthResponse = resp.json().await.map_err(|e| e.to_string())?;
+        Self::session_from_response(response, request.auth_server, None)
+    }
+
+    async fn refresh(&self, app: &AppHandle, session: &AuthSession) -> Result<AuthSession, String> {
+        let api_root = Self::api_root(app, &session.auth_server).await?;
+        let payload = serde_json::json!({
+            "accessToken": session.access_token,
+            "clientToken": session.client_token,
+            "requestUser": true
+        });
+
+        let resp = Self::post(&api_root, "refresh", &payload).await?;
+        if !resp.status().is_success() {
+            return Err(Self::error_text(resp).await);
+        }
+        let response: AuthResponse = resp.json().await.map_err(|e| e.to_string())?;
+        Self::session_from_response(response, session.auth_server.clone(), Some(session))
+    }
+
+    async fn validate(&self, app: &AppHandle, session: &AuthSession) -> Result<bool, String> {
+        let api_root = Self::api_root(app, &session.auth_server).await?;
+        let payload = serde_json::json!({
+            "accessToken": session.access_token,
+            "clientToken": session.client_token
+        });
+        let resp = Self::post(&api_root, "validate", &payload).await?;
+        Ok(resp.status().is_success())
+    }
+
+    async fn logout(&self, app: &AppHandle, session: &AuthSession) -> Result<(), String> {
+        let api_root = Self::api_root(app, &session.auth_server).await?;
+        let payload = serde_json::json!({
+            "accessToken": session.access_token,
+            "clientToken": session.client_token
+        });
+        let resp = Self::post(&api_root, "invalidate", &payload).await?;
+        if !resp.status().is_success() {
+            return Err(Self::error_text(resp).await);
+        }
+        Ok(())
+    }
+
+    async fn launch_arguments(&self, ctx: &LaunchContext, session: &AuthSession) -> Result<LaunchAuth, String> {
+        let server_url = session.auth_server.clone().ok_or("Yggdrasil session has no auth server")?;
+        let app = &ctx.app;
+        let mut jvm_args = Vec::new();
+
+        let pinned_build = crate::launcher::pinned_injector_build(&ctx.version_dir);
+        let injector_path = crate::authlib_injector::ensure_injector(app, &ctx.mc_dir, pinned_build).await?;
+        let _ = app.emit("launch-status", "Fetching auth server metadata...");
+        let auth_state = app.state::<AuthServerState>();
+        match crate::auth_server::get_metadata(&auth_state, &server_url, false).await {
+            Ok(metadata) => {
+                jvm_args.push(format!("-javaagent:{}={}", injector_path.to_string_lossy(), metadata.info.api_root));
+                jvm_args.push(metadata.prefetched_argument());
+            }
+            Err(e) => {
+                // Let authlib-injector resolve the server itself
+                let _ = app.emit("launch-status", format!("Warning: Failed to prefetch auth server metadata: {}", e));
+                jvm_args.push(format!("-javaagent:{}={}", injector_path.to_string_lossy(), server_url));
+            }
+        }
+
+        Ok(LaunchAuth {
+            jvm_args,
+            account: session.to_account("mojang"),
+        })
+    }
+}
@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+const UPDATE_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+const MIRRORS: [&str; 3] = [
+    "https://bmclapi2.bangbang93.com/mirrors/authlib-injector/artifact",
+    "https://openbmclapi.bangbang93.com/mirrors/authlib-injector/artifact",
+    "https://authlib-injector.yushi.moe/artifact",
+];
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArtifactInfo {
+    pub build_number: u32,
+    pub version: String,
+    pub download_url: String,
+    pub checksums: ArtifactChecksums,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArtifactChecksums {
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledInjector {
+    pub build_number: u32,
+    pub version: String,
+    pub sha256: String,
+    pub last_checked: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InjectorStatus {
+    pub installed: Option<InstalledInjector>,
+    pub latest_build: Option<u32>,
+    pub update_available: bool,
+}
+
+fn injector_path(mc_dir: &Path) -> PathBuf {
+    mc_dir.join("authlib-injector.jar")
+}
+
+fn state_path(mc_dir: &Path) -> PathBuf {
+    mc_dir.join("authlib-injector.json")
+}
+
+fn pinned_path(mc_dir: &Path, build_number: u32) -> PathBuf {
+    mc_dir.join("authlib-injector").join(format!("authlib-injector-{}.jar", build_number))
+}
+
+// Every jar on disk was checked against its published hash when it was downloaded, so any of them can stand in
+// for the latest one; the newest pinned build is used when the main jar is missing
+fn present_jar(mc_dir: &Path) -> Option<PathBuf> {
+    let path = injector_path(mc_dir);
+    if path.exists() {
+        return Some(path);
+    }
+    std::fs::read_dir(mc_dir.join("authlib-injector"))
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let build: u32 = name.strip_prefix("authlib-injector-")?.strip_suffix(".jar")?.parse().ok()?;
+            Some((build, entry.path()))
+        })
+        .max_by_key(|(build, _)| *build)
+        .map(|(_, path)| path)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+pub fn load_installed(mc_dir: &Path) -> Option<InstalledInjector> {
+    let content = std::fs::read_to_string(state_path(mc_dir)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_installed(mc_dir: &Path, installed: &InstalledInjector) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(installed).map_err(|e| e.to_string())?;
+    std::fs::write(state_path(mc_dir), content).map_err(|e| e.to_string())
+}
+
+// `build` of None asks for latest.json, otherwise the metadata of that specific build
+async fn fetch_artifact_info(client: &reqwest::Client, build: Option<u32>) -> Result<ArtifactInfo, String> {
+    let name = build.map(|b| b.to_string()).unwrap_or_else(|| "latest".to_string());
+    let mut last_error = String::new();
+
+    for mirror in MIRRORS {
+        let url = format!("{}/{}.json", mirror, name);
+        match client.get(&url).send().await {
+            Ok(resp) if resp.status().is_success() => match resp.json::<ArtifactInfo>().await {
+                Ok(info) => return Ok(info),
+                Err(e) => last_error = format!("JSON parse error from {}: {}", url, e),
+            },
+            Ok(resp) => last_error = format!("Metadata HTTP error from {}: {}", url, resp.status()),
+            Err(e) => last_error = format!("Metadata request error from {}: {}", url, e),
+        }
+    }
+
+    Err(format!("Failed to fetch authlib-injector metadata from any mirror. Last error: {}", last_error))
+}
+
+// Downloads the artifact, checks it against the published SHA-256 and only then
+// moves it over `target`, so a bad download never replaces a working jar.
+async fn download_verified(client: &reqwest::Client, info: &ArtifactInfo, target: &Path) -> Result<(), String> {
+    let resp = client.get(&info.download_url).send().await.map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("File download HTTP error: {}", resp.status()));
+    }
+    let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
+
+    let actual = sha256_hex(&bytes);
+    if !actual.eq_ignore_ascii_case(&info.checksums.sha256) {
+        return Err(format!(
+            "Checksum mismatch for authlib-injector build {} (expected {}, got {})",
+            info.build_number, info.checksums.sha256, actual
+        ));
+    }
+
+    if let Some(parent) = target.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let temp_path = target.with_extension("jar.part");
+    tokio::fs::write(&temp_path, &bytes).await.map_err(|e| e.to_string())?;
+    tokio::fs::rename(&temp_path, target).await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+async fn ensure_pinned(app: &AppHandle, client: &reqwest::Client, mc_dir: &Path, build_number: u32) -> Result<PathBuf, String> {
+    let path = pinned_path(mc_dir, build_number);
+    if path.exists() {
+        return Ok(path);
+    }
+    if crate::http_cache::is_offline() {
+        return Err(format!("authlib-injector build {} is not downloaded and offline mode is on", build_number));
+    }
+
+    let _ = app.emit("launch-status", format!("Downloading authlib-injector build {}...", build_number));
+    let info = fetch_artifact_info(client, Some(build_number)).await?;
+    download_verified(client, &info, &path).await?;
+    Ok(path)
+}
+
+async fn ensure_latest(app: &AppHandle, client: &reqwest::Client, mc_dir: &Path, force_check: bool) -> Result<PathBuf, String> {
+    let path = injector_path(mc_dir);
+    let installed = load_installed(mc_dir).filter(|_| path.exists());
+
+    let due = match &installed {
+        Some(i) => force_check || now_secs().saturating_sub(i.last_checked) >= UPDATE_CHECK_INTERVAL.as_secs(),
+        None => true,
+    };
+    if !due {
+        return Ok(path);
+    }
+    if crate::http_cache::is_offline() {
+        // Whatever jar is on disk will do until we are back online
+        return present_jar(mc_dir).ok_or_else(|| "authlib-injector is not downloaded and offline mode is on".to_string());
+    }
+
+    let _ = app.emit("launch-status", "Checking for authlib-injector updates...");
+    let latest = match fetch_artifact_info(client, None).await {
+        Ok(info) => info,
+        Err(e) => {
+            // An existing jar is still usable when the mirrors are unreachable
+            let _ = app.emit("launch-status", format!("Warning: authlib-injector update check failed: {}", e));
+            return present_jar(mc_dir).ok_or(e);
+        }
+    };
+
+    let up_to_date = installed
+        .as_ref()
+        .map(|i| i.build_number >= latest.build_number)
+        .unwrap_or(false);
+
+    if !up_to_date {
+        let _ = app.emit("launch-status", format!("Downloading authlib-injector {} (build {})...", latest.version, latest.build_number));
+        if let Err(e) = download_verified(client, &latest, &path).await {
+            let _ = app.emit("launch-status", format!("Warning: Failed to update authlib-injector: {}", e));
+            return present_jar(mc_dir).ok_or(e);
+        }
+    }
+
+    let record = match installed {
+        Some(i) if up_to_date => InstalledInjector { last_checked: now_secs(), ..i },
+        _ => InstalledInjector {
+            build_number: latest.build_number,
+            version: latest.version.clone(),
+            sha256: latest.checksums.sha256.to_lowercase(),
+            last_checked: now_secs(),
+        },
+    };
+    save_installed(mc_dir, &record)?;
+    Ok(path)
+}
+
+pub async fn ensure_injector(app: &AppHandle, mc_dir: &Path, pinned_build: Option<u32>) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(mc_dir).map_err(|e| e.to_string())?;
+    let client = reqwest::Client::new();
+    match pinned_build {
+        Some(build) => ensure_pinned(app, &client, mc_dir, build).await,
+        None => ensure_latest(app, &client, mc_dir, false).await,
+    }
+}
+
+#[tauri::command]
+pub async fn check_authlib_injector_update(app: AppHandle, game_path: Option<String>, install: Option<bool>) -> Result<InjectorStatus, String> {
+    let mc_dir = crate::version_path::get_game_root(&app, game_path)?;
+    let client = reqwest::Client::new();
+
+    if install.unwrap_or(false) {
+        std::fs::create_dir_all(&mc_dir).map_err(|e| e.to_string())?;
+        ensure_latest(&app, &client, &mc_dir, true).await?;
+    }
+
+    let installed = load_installed(&mc_dir).filter(|_| injector_path(&mc_dir).exists());
+    let latest_build = fetch_artifact_info(&client, None).await.ok().map(|i| i.build_number);
+    let update_available = match (&installed, latest_build) {
+        (Some(i), Some(latest)) => latest > i.build_number,
+        (None, Some(_)) => true,
+        _ => false,
+    };
+
+    Ok(InjectorStatus {
+        installed,
+        latest_build,
+        update_available,
+    })
+}
@@ -0,0 +1,50 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// Token bucket shared by every download; a rate of 0 means unlimited
+pub struct RateLimiter {
+    bytes_per_sec: AtomicU64,
+    bucket: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    pub fn new(bytes_per_sec: Option<u64>) -> Self {
+        let rate = bytes_per_sec.unwrap_or(0);
+        Self {
+            bytes_per_sec: AtomicU64::new(rate),
+            bucket: Mutex::new((rate as f64, Instant::now())),
+        }
+    }
+
+    pub fn set_limit(&self, bytes_per_sec: Option<u64>) {
+        self.bytes_per_sec.store(bytes_per_sec.unwrap_or(0), Ordering::SeqCst);
+    }
+
+    // Takes `bytes` from the bucket, sleeping off any debt; chunks larger than the bucket are allowed to overdraw it
+    pub async fn acquire(&self, bytes: usize) {
+        let rate = self.bytes_per_sec.load(Ordering::SeqCst);
+        if rate == 0 {
+            return;
+        }
+        let wait = {
+            let Ok(mut bucket) = self.bucket.lock() else {
+                return;
+            };
+            let (tokens, last) = &mut *bucket;
+            let now = Instant::now();
+            // Allow at most one second of burst
+            *tokens = (*tokens + now.duration_since(*last).as_secs_f64() * rate as f64).min(rate as f64);
+            *last = now;
+            *tokens -= bytes as f64;
+            if *tokens < 0.0 {
+                Duration::from_secs_f64(-*tokens / rate as f64)
+            } else {
+                Duration::ZERO
+            }
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
@@ -19,12 +19,59 @@ pub enum IsolationMode {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub isolation_mode: IsolationMode,
+    // "ip:port" of a nameserver used for SRV lookups instead of the system resolver
+    #[serde(default)]
+    pub dns_server: Option<String>,
+    #[serde(default = "default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: usize,
+    // Mirrors like BMCLAPI rate-limit clients that open too many connections
+    #[serde(default = "default_max_connections_per_host")]
+    pub max_connections_per_host: usize,
+    // KiB/s across all downloads, None for unlimited
+    #[serde(default)]
+    pub download_speed_limit_kib: Option<u64>,
+    // Libraries and assets are deduplicated across game roots through a sha1-keyed store
+    #[serde(default)]
+    pub shared_store_enabled: bool,
+    // Defaults to a folder in the app data dir
+    #[serde(default)]
+    pub shared_store_path: Option<String>,
+    // Serve launcher metadata from the cache only and skip network checks when launching
+    #[serde(default)]
+    pub offline_mode: bool,
+    // Cached metadata younger than this is used without revalidating
+    #[serde(default = "default_metadata_cache_ttl_secs")]
+    pub metadata_cache_ttl_secs: u64,
+    // Base URL of a BMCLAPI-compatible OptiFine endpoint, None for BMCLAPI itself
+    #[serde(default)]
+    pub optifine_mirror: Option<String>,
+}
+
+fn default_max_concurrent_downloads() -> usize {
+    10
+}
+
+fn default_max_connections_per_host() -> usize {
+    6
+}
+
+fn default_metadata_cache_ttl_secs() -> u64 {
+    600
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
             isolation_mode: IsolationMode::Modded,
+            dns_server: None,
+            max_concurrent_downloads: default_max_concurrent_downloads(),
+            max_connections_per_host: default_max_connections_per_host(),
+            download_speed_limit_kib: None,
+            shared_store_enabled: false,
+            shared_store_path: None,
+            offline_mode: false,
+            metadata_cache_ttl_secs: default_metadata_cache_ttl_secs(),
+            optifine_mirror: None,
         }
     }
 }
@@ -59,12 +106,17 @@ pub fn save_config(config: &AppConfig) -> Result<(), String> {
     Ok(())
 }
 
+// Manifest types other than "release"; custom types like "modpack" are not snapshots
+pub fn is_snapshot_type(version_type: &str) -> bool {
+    matches!(version_type, "snapshot" | "pending" | "old_beta" | "old_alpha")
+}
+
 pub fn should_isolate(mode: &IsolationMode, is_modded: bool, version_type: &str) -> bool {
     match mode {
         IsolationMode::All => true,
         IsolationMode::Modded => is_modded,
-        IsolationMode::Snapshot => version_type != "release",
-        IsolationMode::ModdedAndSnapshot => is_modded || version_type != "release",
+        IsolationMode::Snapshot => is_snapshot_type(version_type),
+        IsolationMode::ModdedAndSnapshot => is_modded || is_snapshot_type(version_type),
         IsolationMode::None => false,
     }
 }
@@ -0,0 +1,197 @@
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use tauri::{AppHandle, Manager, State};
+
+const DEFAULT_STORE_DIR: &str = "shared_objects";
+const REFS_EXTENSION: &str = "refs";
+
+// Objects live at <root>/<first two hex chars>/<sha1>, next to a <sha1>.refs file listing every path linked to them
+pub struct ContentStore {
+    root: RwLock<Option<PathBuf>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StoreGcReport {
+    pub removed_objects: usize,
+    pub freed_bytes: u64,
+    pub remaining_objects: usize,
+    pub remaining_bytes: u64,
+}
+
+fn resolve_root(app: &AppHandle, enabled: bool, path: Option<&str>) -> Option<PathBuf> {
+    if !enabled {
+        return None;
+    }
+    match path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => Some(PathBuf::from(path)),
+        None => app.path().app_data_dir().ok().map(|dir| dir.join(DEFAULT_STORE_DIR)),
+    }
+}
+
+fn is_sha1(hash: &str) -> bool {
+    hash.len() == 40 && hash.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+pub fn file_sha1(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| e.to_string())?;
+    let mut hasher = Sha1::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+// Hardlinks cost no space; across filesystems fall back to a reflink where supported, or a plain copy
+fn link_or_copy(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::hard_link(from, to).is_ok() {
+        return Ok(());
+    }
+    let temp_path = to.with_extension("store-tmp");
+    reflink_copy::reflink_or_copy(from, &temp_path)?;
+    fs::rename(&temp_path, to)
+}
+
+// Hardlinks share the object's inode; copies and reflinks have to hash to it
+fn still_references(object: &fs::Metadata, sha1: &str, path: &Path) -> bool {
+    let Ok(metadata) = fs::metadata(path) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if metadata.dev() == object.dev() && metadata.ino() == object.ino() {
+            return true;
+        }
+    }
+    metadata.len() == object.len() && file_sha1(path).is_ok_and(|hash| hash == sha1)
+}
+
+impl ContentStore {
+    pub fn load(app: &AppHandle) -> Self {
+        let config = crate::config::load_config();
+        Self {
+            root: RwLock::new(resolve_root(app, config.shared_store_enabled, config.shared_store_path.as_deref())),
+        }
+    }
+
+    fn root(&self) -> Option<PathBuf> {
+        self.root.read().ok().and_then(|root| root.clone())
+    }
+
+    fn object_path(root: &Path, sha1: &str) -> PathBuf {
+        let sha1 = sha1.to_lowercase();
+        root.join(&sha1[..2]).join(&sha1)
+    }
+
+    fn add_ref(object: &Path, target: &Path) {
+        let refs_path = object.with_extension(REFS_EXTENSION);
+        if let Ok(mut file) = fs::OpenOptions::new().create(true).append(true).open(refs_path) {
+            let _ = writeln!(file, "{}", target.to_string_lossy());
+        }
+    }
+
+    // Places the object at `target` if the store has it, returning its size
+    pub fn fetch(&self, sha1: &str, target: &Path) -> Option<u64> {
+        let root = self.root()?;
+        if !is_sha1(sha1) {
+            return None;
+        }
+        let object = Self::object_path(&root, sha1);
+        let size = fs::metadata(&object).ok()?.len();
+        // A hardlinked copy edited in place changes the object too; drop it and let the file be downloaded again
+        if file_sha1(&object).ok()? != sha1.to_lowercase() {
+            let _ = fs::remove_file(&object);
+            let _ = fs::remove_file(object.with_extension(REFS_EXTENSION));
+            return None;
+        }
+        link_or_copy(&object, target).ok()?;
+        Self::add_ref(&object, target);
+        Some(size)
+    }
+
+    // Adds a freshly downloaded and verified file to the store; failures only cost the deduplication
+    pub fn insert(&self, sha1: &str, source: &Path) {
+        let Some(root) = self.root() else {
+            return;
+        };
+        if !is_sha1(sha1) {
+            return;
+        }
+        let object = Self::object_path(&root, sha1);
+        if !object.exists() && link_or_copy(source, &object).is_err() {
+            return;
+        }
+        Self::add_ref(&object, source);
+    }
+
+    // Drops objects that were modified or whose every referencing path was deleted or replaced by a different file
+    fn gc(&self) -> Result<StoreGcReport, String> {
+        let root = self.root().ok_or("The shared store is not enabled")?;
+        let mut report = StoreGcReport { removed_objects: 0, freed_bytes: 0, remaining_objects: 0, remaining_bytes: 0 };
+        if !root.exists() {
+            return Ok(report);
+        }
+        for entry in walkdir::WalkDir::new(&root).min_depth(2).max_depth(2) {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let object = entry.path();
+            let name = entry.file_name().to_string_lossy();
+            if !entry.file_type().is_file() || !is_sha1(&name) {
+                continue;
+            }
+            let metadata = entry.metadata().map_err(|e| e.to_string())?;
+            let size = metadata.len();
+            let refs_path = object.with_extension(REFS_EXTENSION);
+            let intact = file_sha1(object).is_ok_and(|hash| hash == name);
+            let mut refs: Vec<String> = fs::read_to_string(&refs_path)
+                .unwrap_or_default()
+                .lines()
+                .filter(|line| intact && still_references(&metadata, &name, Path::new(line)))
+                .map(|line| line.to_string())
+                .collect();
+            refs.sort();
+            refs.dedup();
+
+            if refs.is_empty() {
+                fs::remove_file(object).map_err(|e| e.to_string())?;
+                let _ = fs::remove_file(&refs_path);
+                report.removed_objects += 1;
+                report.freed_bytes += size;
+            } else {
+                let _ = fs::write(&refs_path, refs.join("\n") + "\n");
+                report.remaining_objects += 1;
+                report.remaining_bytes += size;
+            }
+        }
+        Ok(report)
+    }
+}
+
+#[tauri::command]
+pub fn set_shared_store(app: AppHandle, store: State<'_, ContentStore>, enabled: bool, path: Option<String>) -> Result<(), String> {
+    let mut config = crate::config::load_config();
+    config.shared_store_enabled = enabled;
+    config.shared_store_path = path.filter(|p| !p.trim().is_empty());
+    crate::config::save_config(&config)?;
+    *store.root.write().map_err(|e| e.to_string())? = resolve_root(&app, enabled, config.shared_store_path.as_deref());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn gc_shared_store(app: AppHandle) -> Result<StoreGcReport, String> {
+    // Walking the store hashes objects and stats every referencing file, so keep it off the async workers
+    tauri::async_runtime::spawn_blocking(move || app.state::<ContentStore>().gc())
+        .await
+        .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,909 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use futures::stream::StreamExt;
+use sha1::{Digest, Sha1};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::{oneshot, Notify};
+use tokio::time::sleep;
+
+use crate::bandwidth::RateLimiter;
+use crate::content_store::ContentStore;
+use crate::downloader::{DownloadFileProgress, DownloadLog, DownloadProgress, DownloadState};
+
+const TASKS_FILE: &str = "download_tasks.json";
+// File lists are written once per task next to the index, so state changes only rewrite the small index
+const TASK_FILES_DIR: &str = "download_tasks";
+// Finished tasks are only kept for list_download_tasks, and only this many of them
+const MAX_FINISHED_TASKS: usize = 20;
+const MAX_ATTEMPTS: u32 = 3;
+const BASE_DELAY_MS: u64 = 500;
+// Throughput is measured over windows of this length and smoothed between them
+const SPEED_WINDOW: Duration = Duration::from_secs(1);
+// Progress is aggregated per task and reported at most this often, apart from the final state
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum TaskStatus {
+    #[serde(rename = "queued")]
+    Queued,
+    #[serde(rename = "running")]
+    Running,
+    #[serde(rename = "paused")]
+    Paused,
+    #[serde(rename = "completed")]
+    Completed,
+    #[serde(rename = "failed")]
+    Failed,
+    #[serde(rename = "cancelled")]
+    Cancelled,
+    // Finished downloading after a restart, but the install that queued it never ran and has to be started again
+    #[serde(rename = "interrupted")]
+    Interrupted,
+}
+
+impl TaskStatus {
+    fn is_active(self) -> bool {
+        matches!(self, TaskStatus::Queued | TaskStatus::Running | TaskStatus::Paused)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskFile {
+    pub url: String,
+    pub path: PathBuf,
+    // Bearer tokens are never written to disk; resumed files that needed one will fail and can be retried
+    #[serde(skip)]
+    pub token: Option<String>,
+    // Verified after download when known, and the key into the shared store
+    #[serde(default)]
+    pub sha1: Option<String>,
+}
+
+impl TaskFile {
+    pub fn new(url: String, path: PathBuf, token: Option<String>) -> Self {
+        Self { url, path, token, sha1: None }
+    }
+
+    pub fn with_sha1(mut self, sha1: Option<String>) -> Self {
+        self.sha1 = sha1;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TaskRecord {
+    id: String,
+    // The id progress events are tagged with, as passed by the caller
+    task_id: Option<String>,
+    version_id: String,
+    priority: i32,
+    status: TaskStatus,
+    // Still read from indexes written before the lists moved to their own files
+    #[serde(default, skip_serializing)]
+    files: Vec<TaskFile>,
+    created_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadTaskInfo {
+    pub id: String,
+    pub version_id: String,
+    pub priority: i32,
+    pub status: TaskStatus,
+    pub total_files: usize,
+    pub completed_files: usize,
+    pub failed_files: usize,
+    pub created_at: u64,
+    pub error: Option<String>,
+}
+
+struct Task {
+    record: TaskRecord,
+    total_files: usize,
+    pending: VecDeque<usize>,
+    in_flight: usize,
+    completed: usize,
+    failed: usize,
+    last_error: Option<String>,
+    cancel_flag: Arc<AtomicBool>,
+    waiters: Vec<oneshot::Sender<Result<(), String>>>,
+    // Restored from the last run and not asked for again since
+    restored: bool,
+    // Host of each file, for the per-host connection cap
+    hosts: Vec<String>,
+    // Size of every completed file, including ones that were already on disk
+    finished_bytes: u64,
+    files_in_progress: HashMap<usize, FileState>,
+    // Files that stopped transferring since the last progress event
+    finished_files: Vec<(String, &'static str)>,
+    current: Option<usize>,
+    // Set whenever something changed that the next progress tick should report
+    dirty: bool,
+    window_start: Instant,
+    window_bytes: u64,
+    speed: Option<f64>,
+}
+
+struct FileState {
+    downloaded: u64,
+    size: Option<u64>,
+}
+
+fn file_host(file: &TaskFile) -> String {
+    reqwest::Url::parse(&file.url)
+        .ok()
+        .and_then(|url| url.host_str().map(|h| h.to_lowercase()))
+        .unwrap_or_default()
+}
+
+fn file_name(file: &TaskFile) -> String {
+    file.path.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
+
+impl Task {
+    fn new(record: TaskRecord, cancel_flag: Arc<AtomicBool>) -> Self {
+        Self {
+            total_files: record.files.len(),
+            pending: (0..record.files.len()).collect(),
+            hosts: record.files.iter().map(file_host).collect(),
+            record,
+            in_flight: 0,
+            completed: 0,
+            failed: 0,
+            last_error: None,
+            cancel_flag,
+            waiters: Vec::new(),
+            restored: false,
+            finished_bytes: 0,
+            files_in_progress: HashMap::new(),
+            finished_files: Vec::new(),
+            current: None,
+            dirty: false,
+            window_start: Instant::now(),
+            window_bytes: 0,
+            speed: None,
+        }
+    }
+
+    fn reset_progress(&mut self) {
+        self.total_files = self.record.files.len();
+        self.pending = (0..self.record.files.len()).collect();
+        self.hosts = self.record.files.iter().map(file_host).collect();
+        self.completed = 0;
+        self.failed = 0;
+        self.finished_bytes = 0;
+        self.files_in_progress.clear();
+        self.finished_files.clear();
+        self.current = None;
+        self.window_start = Instant::now();
+        self.window_bytes = 0;
+        self.speed = None;
+    }
+
+    // Closes the throughput window once it is long enough; also called on ticks so a stalled download drops to zero
+    fn roll_speed_window(&mut self) {
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= SPEED_WINDOW {
+            let current = self.window_bytes as f64 / elapsed.as_secs_f64();
+            self.speed = Some(match self.speed {
+                Some(previous) => previous * 0.7 + current * 0.3,
+                None => current,
+            });
+            self.window_start = Instant::now();
+            self.window_bytes = 0;
+            self.dirty = true;
+        }
+    }
+
+    // Files whose size isn't known yet are assumed to be the average of the ones that are
+    fn bytes(&self) -> (u64, Option<u64>) {
+        let downloaded = self.finished_bytes + self.files_in_progress.values().map(|f| f.downloaded).sum::<u64>();
+        let sizes: Vec<u64> = self.files_in_progress.values().filter_map(|f| f.size).collect();
+        let sized_files = self.completed + sizes.len();
+        if sized_files == 0 {
+            return (downloaded, None);
+        }
+        let known = self.finished_bytes + sizes.iter().sum::<u64>();
+        let unknown = self.record.files.len().saturating_sub(self.failed + sized_files);
+        let total = known + known / sized_files as u64 * unknown as u64;
+        (downloaded, Some(total.max(downloaded)))
+    }
+
+    fn progress_event(&self) -> DownloadProgress {
+        let total_files = self.record.files.len();
+        let (downloaded_bytes, total_bytes) = self.bytes();
+        let eta_seconds = match (self.speed.filter(|s| *s > 0.0), total_bytes) {
+            (Some(speed), Some(total)) => Some((total.saturating_sub(downloaded_bytes) as f64 / speed).ceil() as u64),
+            _ => None,
+        };
+        let current = self.current.and_then(|i| self.files_in_progress.get(&i));
+        DownloadProgress {
+            task_id: self.record.task_id.clone(),
+            version_id: self.record.version_id.clone(),
+            total_files,
+            downloaded_files: self.completed,
+            current_file: self.current.map(|i| file_name(&self.record.files[i])).unwrap_or_default(),
+            percent: if total_files == 0 { 100.0 } else { (self.completed as f64 / total_files as f64) * 100.0 },
+            current_file_progress: current.and_then(|f| f.size.map(|size| (f.downloaded as f64 / size.max(1) as f64) * 100.0)),
+            current_file_downloaded: current.map(|f| f.downloaded),
+            current_file_total: current.and_then(|f| f.size),
+            speed: self.speed,
+            eta_seconds,
+            downloaded_bytes: Some(downloaded_bytes),
+            total_bytes,
+        }
+    }
+
+    fn take_file_events(&mut self) -> Vec<DownloadFileProgress> {
+        let mut events: Vec<DownloadFileProgress> = self
+            .finished_files
+            .drain(..)
+            .map(|(filename, status)| DownloadFileProgress {
+                task_id: self.record.task_id.clone(),
+                filename,
+                progress: 100.0,
+                status: status.to_string(),
+            })
+            .collect();
+        for (index, file) in &self.files_in_progress {
+            events.push(DownloadFileProgress {
+                task_id: self.record.task_id.clone(),
+                filename: file_name(&self.record.files[*index]),
+                progress: file.size.map(|size| (file.downloaded as f64 / size.max(1) as f64) * 100.0).unwrap_or(0.0),
+                status: "downloading".to_string(),
+            });
+        }
+        events
+    }
+
+    fn emit_progress(&mut self, app: &AppHandle) {
+        self.dirty = false;
+        for event in self.take_file_events() {
+            let _ = app.emit("download-file-progress", event);
+        }
+        let _ = app.emit("download-progress", self.progress_event());
+    }
+
+    fn info(&self) -> DownloadTaskInfo {
+        DownloadTaskInfo {
+            id: self.record.id.clone(),
+            version_id: self.record.version_id.clone(),
+            priority: self.record.priority,
+            status: self.record.status,
+            total_files: self.total_files,
+            completed_files: self.completed,
+            failed_files: self.failed,
+            created_at: self.record.created_at,
+            error: self.last_error.clone(),
+        }
+    }
+
+    // Drops everything a finished task no longer needs, keeping what info() reports
+    fn compact(&mut self) {
+        self.record.files = Vec::new();
+        self.hosts = Vec::new();
+        self.pending = VecDeque::new();
+        self.files_in_progress = HashMap::new();
+        self.finished_files = Vec::new();
+        self.current = None;
+    }
+}
+
+pub struct FileJob {
+    pub id: String,
+    pub task_id: Option<String>,
+    pub index: usize,
+    pub file: TaskFile,
+    pub host: String,
+    pub cancel_flag: Arc<AtomicBool>,
+}
+
+// Single queue shared by every install, import and deploy; files are handed out to workers by task priority
+pub struct DownloadManager {
+    tasks: Mutex<Vec<Task>>,
+    wake: Notify,
+    max_concurrent: AtomicUsize,
+    in_flight: AtomicUsize,
+    max_per_host: AtomicUsize,
+    // Open connections per host; always locked after `tasks`
+    host_connections: Mutex<HashMap<String, usize>>,
+    limiter: RateLimiter,
+    storage_path: Option<PathBuf>,
+    files_dir: Option<PathBuf>,
+}
+
+fn task_files_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{:x}.json", Sha1::digest(id.as_bytes())))
+}
+
+fn prune_finished(tasks: &mut Vec<Task>) {
+    let finished = tasks.iter().filter(|t| !t.record.status.is_active()).count();
+    let mut excess = finished.saturating_sub(MAX_FINISHED_TASKS);
+    // Tasks are kept in the order they were added, so the oldest go first
+    tasks.retain(|t| {
+        if excess > 0 && !t.record.status.is_active() {
+            excess -= 1;
+            return false;
+        }
+        true
+    });
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+impl DownloadManager {
+    // Restores unfinished tasks from the previous run; they start again once the dispatcher is running
+    pub fn load(app: &AppHandle) -> Self {
+        let data_dir = app.path().app_data_dir().ok();
+        let storage_path = data_dir.as_ref().map(|dir| dir.join(TASKS_FILE));
+        let files_dir = data_dir.map(|dir| dir.join(TASK_FILES_DIR));
+        let records: Vec<TaskRecord> = storage_path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let download_state = app.state::<DownloadState>();
+        let tasks: Vec<Task> = records
+            .into_iter()
+            .filter(|r| r.status.is_active())
+            .filter_map(|mut record| {
+                let path = task_files_path(files_dir.as_ref()?, &record.id);
+                if record.files.is_empty() {
+                    record.files = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+                } else if let Ok(content) = serde_json::to_string(&record.files) {
+                    let _ = fs::create_dir_all(path.parent()?);
+                    let _ = fs::write(&path, content);
+                }
+                if record.status == TaskStatus::Running {
+                    record.status = TaskStatus::Queued;
+                }
+                let cancel_flag = download_state.get_cancel_flag(&record.id);
+                let mut task = Task::new(record, cancel_flag);
+                task.restored = true;
+                Some(task)
+            })
+            .collect();
+        // Lists left behind by tasks that finished or couldn't be restored
+        if let Some(entries) = files_dir.as_ref().and_then(|dir| fs::read_dir(dir).ok()) {
+            let kept: Vec<PathBuf> = tasks.iter().filter_map(|t| Some(task_files_path(files_dir.as_ref()?, &t.record.id))).collect();
+            for entry in entries.flatten() {
+                if !kept.contains(&entry.path()) {
+                    let _ = fs::remove_file(entry.path());
+                }
+            }
+        }
+
+        let config = crate::config::load_config();
+        Self {
+            tasks: Mutex::new(tasks),
+            wake: Notify::new(),
+            max_concurrent: AtomicUsize::new(config.max_concurrent_downloads.max(1)),
+            in_flight: AtomicUsize::new(0),
+            max_per_host: AtomicUsize::new(config.max_connections_per_host.max(1)),
+            host_connections: Mutex::new(HashMap::new()),
+            limiter: RateLimiter::new(config.download_speed_limit_kib.map(|kib| kib * 1024)),
+            storage_path,
+            files_dir,
+        }
+    }
+
+    fn persist(&self, tasks: &[Task]) {
+        let Some(path) = &self.storage_path else {
+            return;
+        };
+        let records: Vec<&TaskRecord> = tasks.iter().map(|t| &t.record).filter(|r| r.status.is_active()).collect();
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string(&records) {
+            let _ = fs::write(path, content);
+        }
+    }
+
+    fn persist_files(&self, id: &str, content: &str) {
+        let Some(dir) = &self.files_dir else {
+            return;
+        };
+        let _ = fs::create_dir_all(dir);
+        let _ = fs::write(task_files_path(dir, id), content);
+    }
+
+    fn remove_files(&self, id: &str) {
+        if let Some(dir) = &self.files_dir {
+            let _ = fs::remove_file(task_files_path(dir, id));
+        }
+    }
+
+    fn updated(&self, app: &AppHandle, tasks: &[Task], task: &Task) {
+        self.persist(tasks);
+        let _ = app.emit("download-task-updated", task.info());
+    }
+
+    pub fn enqueue(
+        &self,
+        app: &AppHandle,
+        id: String,
+        task_id: Option<String>,
+        version_id: &str,
+        files: Vec<TaskFile>,
+        cancel_flag: Arc<AtomicBool>
+    ) -> Result<oneshot::Receiver<Result<(), String>>, String> {
+        let (tx, rx) = oneshot::channel();
+        // Serialized before taking the lock, it can be large for a full modpack
+        let files_content = serde_json::to_string(&files).map_err(|e| e.to_string())?;
+        let mut tasks = self.tasks.lock().map_err(|e| e.to_string())?;
+        if tasks.iter().any(|t| t.record.id == id && t.record.status.is_active() && !t.waiters.is_empty()) {
+            return Err("Download already in progress".to_string());
+        }
+        prune_finished(&mut tasks);
+        let mut write_files = true;
+        // A task restored from the last run is picked up by whoever asks for it again
+        let position = match tasks.iter().position(|t| t.record.id == id) {
+            Some(position) if tasks[position].record.status.is_active() => {
+                let task = &mut tasks[position];
+                task.record.task_id = task_id;
+                task.restored = false;
+                if task.in_flight == 0 {
+                    task.record.files = files;
+                    task.reset_progress();
+                } else {
+                    // Its files are already being counted; keep them and only take over the tokens that weren't saved
+                    let tokens: HashMap<PathBuf, String> = files.into_iter().filter_map(|f| Some((f.path, f.token?))).collect();
+                    for file in task.record.files.iter_mut() {
+                        file.token = tokens.get(&file.path).cloned();
+                    }
+                    write_files = false;
+                }
+                position
+            }
+            existing => {
+                if let Some(position) = existing {
+                    tasks.remove(position);
+                }
+                let record = TaskRecord {
+                    id,
+                    task_id,
+                    version_id: version_id.to_string(),
+                    priority: 0,
+                    status: TaskStatus::Queued,
+                    files,
+                    created_at: now_millis(),
+                };
+                tasks.push(Task::new(record, cancel_flag));
+                tasks.len() - 1
+            }
+        };
+        tasks[position].waiters.push(tx);
+        if tasks[position].record.files.is_empty() {
+            self.finish_task(app, &mut tasks, position);
+            write_files = false;
+        } else {
+            self.updated(app, &tasks, &tasks[position]);
+        }
+        let id = tasks[position].record.id.clone();
+        drop(tasks);
+        // A list left behind by a task that finished meanwhile is cleaned up on the next start
+        if write_files {
+            self.persist_files(&id, &files_content);
+        }
+        self.wake.notify_one();
+        Ok(rx)
+    }
+
+    // Next file of the highest-priority runnable task, respecting the global and per-host limits
+    fn next_job(&self, app: &AppHandle) -> Option<FileJob> {
+        if self.in_flight.load(Ordering::SeqCst) >= self.max_concurrent.load(Ordering::SeqCst) {
+            return None;
+        }
+        let mut tasks = self.tasks.lock().ok()?;
+        let mut hosts = self.host_connections.lock().ok()?;
+        let max_per_host = self.max_per_host.load(Ordering::SeqCst);
+
+        let mut runnable: Vec<usize> = (0..tasks.len())
+            .filter(|i| matches!(tasks[*i].record.status, TaskStatus::Queued | TaskStatus::Running) && !tasks[*i].pending.is_empty())
+            .collect();
+        // Stable sort keeps older tasks first within the same priority
+        runnable.sort_by_key(|i| std::cmp::Reverse(tasks[*i].record.priority));
+
+        // Skip files whose host is saturated so other mirrors keep working
+        let (position, slot) = runnable.into_iter().find_map(|i| {
+            let task = &tasks[i];
+            task.pending
+                .iter()
+                .position(|index| hosts.get(&task.hosts[*index]).copied().unwrap_or(0) < max_per_host)
+                .map(|slot| (i, slot))
+        })?;
+
+        let task = &mut tasks[position];
+        let index = task.pending.remove(slot)?;
+        let host = task.hosts[index].clone();
+        *hosts.entry(host.clone()).or_insert(0) += 1;
+        drop(hosts);
+        task.in_flight += 1;
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let job = FileJob {
+            id: task.record.id.clone(),
+            task_id: task.record.task_id.clone(),
+            index,
+            file: task.record.files[index].clone(),
+            host,
+            cancel_flag: task.cancel_flag.clone(),
+        };
+        if task.record.status == TaskStatus::Queued {
+            task.record.status = TaskStatus::Running;
+            self.updated(app, &tasks, &tasks[position]);
+        }
+        Some(job)
+    }
+
+    fn finish_job(&self, app: &AppHandle, job: &FileJob, result: Result<u64, String>) {
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        if let Ok(mut tasks) = self.tasks.lock() {
+            if let Ok(mut hosts) = self.host_connections.lock() {
+                if let Some(count) = hosts.get_mut(&job.host) {
+                    *count = count.saturating_sub(1);
+                    if *count == 0 {
+                        hosts.remove(&job.host);
+                    }
+                }
+            }
+            if let Some(position) = tasks.iter().position(|t| t.record.id == job.id) {
+                let task = &mut tasks[position];
+                task.in_flight -= 1;
+                // Files found on disk never showed up as transferring, so they need no file event
+                let transferring = task.files_in_progress.remove(&job.index).is_some();
+                let filename = file_name(&job.file);
+                match result {
+                    Ok(bytes) => {
+                        task.completed += 1;
+                        task.finished_bytes += bytes;
+                        if transferring {
+                            task.finished_files.push((filename, "done"));
+                        }
+                    }
+                    Err(e) => {
+                        task.failed += 1;
+                        task.last_error = Some(e);
+                        if transferring {
+                            task.finished_files.push((filename, "failed"));
+                        }
+                    }
+                }
+                // Keep naming a file in the progress even while only already-present ones are being skipped
+                if !task.current.is_some_and(|i| task.files_in_progress.contains_key(&i)) {
+                    task.current = Some(job.index);
+                }
+                task.dirty = true;
+                if task.in_flight == 0 && (task.pending.is_empty() || task.record.status == TaskStatus::Cancelled) {
+                    self.finish_task(app, &mut tasks, position);
+                }
+            }
+        }
+        self.wake.notify_one();
+    }
+
+    fn finish_task(&self, app: &AppHandle, tasks: &mut [Task], position: usize) {
+        let task = &mut tasks[position];
+        // Individual file failures are logged but, as before, don't fail the caller
+        let result = if task.record.status == TaskStatus::Cancelled || task.cancel_flag.load(Ordering::Relaxed) {
+            task.record.status = TaskStatus::Cancelled;
+            Err("Download cancelled".to_string())
+        } else {
+            task.record.status = if task.failed > 0 {
+                TaskStatus::Failed
+            } else if task.restored && task.waiters.is_empty() {
+                // Nothing is left to run the install steps, so the version isn't usable yet
+                task.last_error = Some("Downloaded after a restart; start the install again to finish it".to_string());
+                TaskStatus::Interrupted
+            } else {
+                TaskStatus::Completed
+            };
+            Ok(())
+        };
+        // The final state goes out right away instead of waiting for the next tick
+        task.emit_progress(app);
+        for waiter in task.waiters.drain(..) {
+            let _ = waiter.send(result.clone());
+        }
+        app.state::<DownloadState>().clear_cancel(&task.record.id);
+        self.remove_files(&task.record.id);
+        self.updated(app, tasks, &tasks[position]);
+        tasks[position].compact();
+    }
+
+    fn update_task<F: FnOnce(&mut Task) -> Result<(), String>>(&self, app: &AppHandle, id: &str, f: F) -> Result<(), String> {
+        let mut tasks = self.tasks.lock().map_err(|e| e.to_string())?;
+        let position = tasks
+            .iter()
+            .position(|t| t.record.id == id)
+            .ok_or_else(|| format!("Download task {} not found", id))?;
+        f(&mut tasks[position])?;
+        self.updated(app, &tasks, &tasks[position]);
+        drop(tasks);
+        self.wake.notify_one();
+        Ok(())
+    }
+
+    pub fn cancel(&self, app: &AppHandle, id: &str) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            if let Some(position) = tasks.iter().position(|t| t.record.id == id && t.record.status.is_active()) {
+                let task = &mut tasks[position];
+                task.record.status = TaskStatus::Cancelled;
+                task.pending.clear();
+                // In-flight files notice the cancel flag and finish the task when they stop
+                if task.in_flight == 0 {
+                    self.finish_task(app, &mut tasks, position);
+                } else {
+                    self.updated(app, &tasks, &tasks[position]);
+                }
+            }
+        }
+    }
+
+    pub fn tasks(&self) -> Vec<DownloadTaskInfo> {
+        self.tasks
+            .lock()
+            .map(|tasks| tasks.iter().map(|t| t.info()).collect())
+            .unwrap_or_default()
+    }
+
+    // Waits until the global bandwidth limit allows `bytes` more
+    pub async fn throttle(&self, bytes: usize) {
+        self.limiter.acquire(bytes).await;
+    }
+
+    fn with_file<F: FnOnce(&mut Task)>(&self, job: &FileJob, f: F) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            if let Some(task) = tasks.iter_mut().find(|t| t.record.id == job.id) {
+                f(task);
+                task.dirty = true;
+            }
+        }
+    }
+
+    // Called for every attempt, so a retry starts the file's byte count over
+    fn file_started(&self, job: &FileJob, size: Option<u64>) {
+        self.with_file(job, |task| {
+            task.files_in_progress.insert(job.index, FileState { downloaded: 0, size });
+            task.current = Some(job.index);
+        });
+    }
+
+    fn file_progress(&self, job: &FileJob, bytes: u64) {
+        self.with_file(job, |task| {
+            if let Some(file) = task.files_in_progress.get_mut(&job.index) {
+                file.downloaded += bytes;
+            }
+            task.current = Some(job.index);
+            task.window_bytes += bytes;
+            task.roll_speed_window();
+        });
+    }
+
+    // Reports every task that changed since the last tick
+    fn flush_progress(&self, app: &AppHandle) {
+        if let Ok(mut tasks) = self.tasks.lock() {
+            for task in tasks.iter_mut() {
+                if task.in_flight > 0 {
+                    task.roll_speed_window();
+                }
+                if task.dirty {
+                    task.emit_progress(app);
+                }
+            }
+        }
+    }
+}
+
+pub fn start(app: AppHandle) {
+    let dispatcher_app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let app = dispatcher_app;
+        let client = reqwest::Client::new();
+        loop {
+            let manager = app.state::<DownloadManager>();
+            while let Some(job) = manager.next_job(&app) {
+                let app = app.clone();
+                let client = client.clone();
+                tauri::async_runtime::spawn(async move {
+                    let result = download_file(&app, &client, &job).await;
+                    if let Err(e) = &result {
+                        let _ = app.emit("download-log", DownloadLog { task_id: job.task_id.clone(), message: format!("Download error: {}", e), level: "error".to_string() });
+                    }
+                    app.state::<DownloadManager>().finish_job(&app, &job, result);
+                });
+            }
+            manager.wake.notified().await;
+        }
+    });
+
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(PROGRESS_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            ticker.tick().await;
+            app.state::<DownloadManager>().flush_progress(&app);
+        }
+    });
+}
+
+// Returns the size of the file, whether it was fetched or already present
+async fn download_file(app: &AppHandle, client: &reqwest::Client, job: &FileJob) -> Result<u64, String> {
+    let path = &job.file.path;
+    let filename = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    if job.cancel_flag.load(Ordering::Relaxed) {
+        return Err("Download cancelled".to_string());
+    }
+    if let Ok(metadata) = fs::metadata(path) {
+        return Ok(metadata.len());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    // Store hits hash the whole object before linking it, which has no place on the async workers
+    if let Some(sha1) = job.file.sha1.clone() {
+        let (store_app, target) = (app.clone(), path.clone());
+        let fetched = tauri::async_runtime::spawn_blocking(move || store_app.state::<ContentStore>().fetch(&sha1, &target)).await;
+        if let Ok(Some(size)) = fetched {
+            return Ok(size);
+        }
+    }
+    let store = app.state::<ContentStore>();
+
+    let _ = app.emit("download-log", DownloadLog { task_id: job.task_id.clone(), message: format!("Downloading {}", filename), level: "info".to_string() });
+
+    // Download next to the target and rename at the end, so an interrupted file is never mistaken for a complete one
+    let part_path = path.with_file_name(format!("{}.part", filename));
+    let manager = app.state::<DownloadManager>();
+    let mut last_error: Option<String> = None;
+
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut req = client.get(&job.file.url);
+        if let Some(t) = job.file.token.clone() {
+            req = req.header("Authorization", format!("Bearer {}", t));
+        }
+
+        let result: Result<u64, String> = async {
+            let resp = req.send().await.map_err(|e| e.to_string())?;
+            if !resp.status().is_success() {
+                return Err(format!("HTTP {}", resp.status()));
+            }
+            let total = resp.content_length();
+            manager.file_started(job, total);
+            let mut downloaded_bytes = 0u64;
+            let mut file = fs::File::create(&part_path).map_err(|e| e.to_string())?;
+            let mut stream = resp.bytes_stream();
+            let mut hasher = Sha1::new();
+
+            while let Some(chunk) = stream.next().await {
+                if job.cancel_flag.load(Ordering::Relaxed) {
+                    return Err("Download cancelled".to_string());
+                }
+                let chunk = chunk.map_err(|e| e.to_string())?;
+                manager.throttle(chunk.len()).await;
+                file.write_all(&chunk).map_err(|e| e.to_string())?;
+                hasher.update(&chunk);
+                downloaded_bytes += chunk.len() as u64;
+                manager.file_progress(job, chunk.len() as u64);
+            }
+            drop(file);
+            if let Some(expected) = &job.file.sha1 {
+                let actual = format!("{:x}", hasher.finalize());
+                if !actual.eq_ignore_ascii_case(expected) {
+                    return Err(format!("SHA1 mismatch for {}: expected {}, got {}", filename, expected, actual));
+                }
+            }
+            fs::rename(&part_path, path).map_err(|e| e.to_string())?;
+            if let Some(sha1) = &job.file.sha1 {
+                store.insert(sha1, path);
+            }
+            Ok(downloaded_bytes)
+        }
+        .await;
+
+        match result {
+            Ok(bytes) => return Ok(bytes),
+            Err(e) => {
+                let _ = fs::remove_file(&part_path);
+                if job.cancel_flag.load(Ordering::Relaxed) {
+                    return Err(e);
+                }
+                last_error = Some(e);
+                if attempt < MAX_ATTEMPTS {
+                    let delay = BASE_DELAY_MS * 2u64.pow(attempt - 1);
+                    let _ = app.emit(
+                        "download-log",
+                        DownloadLog {
+                            task_id: job.task_id.clone(),
+                            message: format!("Retry {}/{} for {} in {}ms", attempt, MAX_ATTEMPTS, filename, delay),
+                            level: "warn".to_string(),
+                        },
+                    );
+                    sleep(Duration::from_millis(delay)).await;
+                }
+            }
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| "Download failed".to_string()))
+}
+
+#[tauri::command]
+pub fn list_download_tasks(manager: State<'_, DownloadManager>) -> Vec<DownloadTaskInfo> {
+    manager.tasks()
+}
+
+#[tauri::command]
+pub fn pause_download_task(app: AppHandle, manager: State<'_, DownloadManager>, task_id: String) -> Result<(), String> {
+    manager.update_task(&app, &task_id, |task| match task.record.status {
+        TaskStatus::Queued | TaskStatus::Running => {
+            // Files already transferring are allowed to finish
+            task.record.status = TaskStatus::Paused;
+            Ok(())
+        }
+        TaskStatus::Paused => Ok(()),
+        _ => Err("Only queued or running tasks can be paused".to_string()),
+    })
+}
+
+#[tauri::command]
+pub fn resume_download_task(app: AppHandle, manager: State<'_, DownloadManager>, task_id: String) -> Result<(), String> {
+    manager.update_task(&app, &task_id, |task| {
+        if task.record.status == TaskStatus::Paused {
+            task.record.status = TaskStatus::Queued;
+        }
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn set_download_task_priority(app: AppHandle, manager: State<'_, DownloadManager>, task_id: String, priority: i32) -> Result<(), String> {
+    manager.update_task(&app, &task_id, |task| {
+        task.record.priority = priority;
+        Ok(())
+    })
+}
+
+#[tauri::command]
+pub fn set_max_concurrent_downloads(manager: State<'_, DownloadManager>, limit: usize) -> Result<(), String> {
+    let limit = limit.max(1);
+    let mut config = crate::config::load_config();
+    config.max_concurrent_downloads = limit;
+    crate::config::save_config(&config)?;
+    manager.max_concurrent.store(limit, Ordering::SeqCst);
+    manager.wake.notify_one();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_download_speed_limit(manager: State<'_, DownloadManager>, limit_kib: Option<u64>) -> Result<(), String> {
+    let limit_kib = limit_kib.filter(|kib| *kib > 0);
+    let mut config = crate::config::load_config();
+    config.download_speed_limit_kib = limit_kib;
+    crate::config::save_config(&config)?;
+    manager.limiter.set_limit(limit_kib.map(|kib| kib * 1024));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_max_connections_per_host(manager: State<'_, DownloadManager>, limit: usize) -> Result<(), String> {
+    let limit = limit.max(1);
+    let mut config = crate::config::load_config();
+    config.max_connections_per_host = limit;
+    crate::config::save_config(&config)?;
+    manager.max_per_host.store(limit, Ordering::SeqCst);
+    manager.wake.notify_one();
+    Ok(())
+}
@@ -2,15 +2,13 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::sync::atomic::{AtomicBool, Ordering};
-use tokio::sync::Semaphore;
 use tauri::{AppHandle, Emitter, Manager, State};
 use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::io::{BufRead, BufReader, Write};
-use std::process::{Command, Stdio};
-use std::time::Duration;
+use std::io::{Read, Write};
 use futures::stream::StreamExt;
-use tokio::time::sleep;
+
+use crate::download_manager::TaskFile;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct FabricLoaderVersion {
@@ -96,7 +94,8 @@ pub struct VersionEntry {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct VersionDetails {
-    pub downloads: VersionDownloads,
+    // Optional so jsons whose jar ships alongside them (experimental snapshot zips) still parse
+    pub downloads: Option<VersionDownloads>,
     pub libraries: Vec<Library>,
     #[serde(rename = "assetIndex")]
     pub asset_index: AssetIndex,
@@ -197,6 +196,12 @@ pub struct DownloadProgress {
     pub current_file_progress: Option<f64>,
     pub current_file_downloaded: Option<u64>,
     pub current_file_total: Option<u64>,
+    // Bytes per second over the last moments of the task
+    pub speed: Option<f64>,
+    pub eta_seconds: Option<u64>,
+    // Across the whole task; the total is estimated from the files sized so far until all have started
+    pub downloaded_bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
 }
 
 #[derive(Clone, Serialize)]
@@ -211,8 +216,9 @@ fn get_cancel_key(version_id: &str, task_id: &Option<String>) -> String {
 }
 
 #[tauri::command]
-pub fn cancel_download(state: State<'_, DownloadState>, task_id: String) -> Result<(), String> {
+pub fn cancel_download(app: AppHandle, state: State<'_, DownloadState>, task_id: String) -> Result<(), String> {
     state.cancel(&task_id);
+    app.state::<crate::download_manager::DownloadManager>().cancel(&app, &task_id);
     Ok(())
 }
 
@@ -227,17 +233,10 @@ pub struct DownloadFileProgress {
 const MANIFEST_URL: &str = "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
 
 #[tauri::command]
-pub async fn fetch_manifest() -> Result<Vec<VersionEntry>, String> {
+pub async fn fetch_manifest(app: AppHandle) -> Result<Vec<VersionEntry>, String> {
     let client = reqwest::Client::new();
-    let manifest: VersionManifest = client
-        .get(MANIFEST_URL)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .json()
-        .await
-        .map_err(|e| e.to_string())?;
-    
+    let manifest: VersionManifest = crate::http_cache::get_json(&app, &client, MANIFEST_URL).await?;
+
     Ok(manifest.versions)
 }
 
@@ -249,24 +248,33 @@ struct NeoForgeVersion {
 }
 
 #[tauri::command]
-pub async fn fetch_loaders(game_version: String, loader_type: String) -> Result<Vec<String>, String> {
+pub async fn fetch_loaders(app: AppHandle, game_version: String, loader_type: String) -> Result<Vec<String>, String> {
     let client = reqwest::Client::new();
     match loader_type.as_str() {
         "fabric" => {
             let url = format!("https://bmclapi2.bangbang93.com/fabric-meta/v2/versions/loader/{}", game_version);
-            let resp: Vec<FabricLoaderVersion> = client.get(&url).send().await.map_err(|e| e.to_string())?.json().await.map_err(|e| e.to_string())?;
+            let resp: Vec<FabricLoaderVersion> = crate::http_cache::get_json(&app, &client, &url).await?;
+            Ok(resp.into_iter().map(|v| v.loader.version).collect())
+        },
+        "quilt" => {
+            let url = format!("https://meta.quiltmc.org/v3/versions/loader/{}", game_version);
+            let resp: Vec<FabricLoaderVersion> = crate::http_cache::get_json(&app, &client, &url).await?;
             Ok(resp.into_iter().map(|v| v.loader.version).collect())
         },
         "forge" => {
              let url = format!("https://bmclapi2.bangbang93.com/forge/minecraft/{}", game_version);
-             let resp: Vec<ForgeVersion> = client.get(&url).send().await.map_err(|e| e.to_string())?.json().await.map_err(|e| e.to_string())?;
+             let resp: Vec<ForgeVersion> = crate::http_cache::get_json(&app, &client, &url).await?;
              Ok(resp.into_iter().map(|v| v.version).collect())
         },
         "neoforge" => {
              let url = format!("https://bmclapi2.bangbang93.com/neoforge/list/{}", game_version);
-             let resp: Vec<NeoForgeVersion> = client.get(&url).send().await.map_err(|e| e.to_string())?.json().await.map_err(|e| e.to_string())?;
+             let resp: Vec<NeoForgeVersion> = crate::http_cache::get_json(&app, &client, &url).await?;
              Ok(resp.into_iter().map(|v| v.version).collect())
         },
+        "optifine" => {
+             let builds = crate::optifine::fetch_builds(&app, &client, &game_version).await?;
+             Ok(builds.into_iter().map(|b| b.id()).collect())
+        },
         _ => Ok(Vec::new())
     }
 }
@@ -327,16 +335,21 @@ pub async fn install_version(
     });
 
     // 1. Prepare Vanilla Downloads
-    let (mut download_queue, _version_details) = prepare_vanilla_downloads(&client, &version_id, &mc_dir).await?;
+    let (mut download_queue, _version_details) = prepare_vanilla_downloads(&app, &client, &version_id, &mc_dir).await?;
 
     // 2. Prepare Loader Downloads (if applicable)
     if loader_type == "fabric" {
         if let Some(l_ver) = &loader_version {
-            let (loader_queue, _) = prepare_fabric_downloads(&client, &version_id, l_ver, &mc_dir).await?;
+            let (loader_queue, _) = prepare_fabric_downloads(&app, &client, &version_id, l_ver, &mc_dir).await?;
+            download_queue.extend(loader_queue);
+        }
+    } else if loader_type == "quilt" {
+        if let Some(l_ver) = &loader_version {
+            let (loader_queue, _) = prepare_quilt_downloads(&app, &client, &version_id, l_ver, &mc_dir).await?;
             download_queue.extend(loader_queue);
         }
-    } else if loader_type == "forge" || loader_type == "neoforge" {
-        // For Forge/NeoForge, we download the installer and run it.
+    } else if loader_type == "forge" || loader_type == "neoforge" || loader_type == "optifine" {
+        // For Forge/NeoForge/OptiFine, we download the installer and run it.
         // We do this AFTER downloading vanilla files to ensure assets are present.
     } else if loader_type != "vanilla" {
         return Err(format!("Loader type '{}' is not yet supported for installation.", loader_type));
@@ -348,7 +361,11 @@ pub async fn install_version(
     // 4. Run Installer for Forge/NeoForge
     if loader_type == "forge" || loader_type == "neoforge" {
         if let Some(l_ver) = &loader_version {
-            install_forge_or_neoforge(&app, &client, &version_id, &loader_type, l_ver, &mc_dir, java_path, task_id).await?;
+            install_forge_or_neoforge(&app, &version_id, &loader_type, l_ver, &mc_dir, java_path, task_id).await?;
+        }
+    } else if loader_type == "optifine" {
+        if let Some(l_ver) = &loader_version {
+            crate::optifine::install(&app, &client, &version_id, l_ver, &mc_dir, java_path, task_id).await?;
         }
     }
 
@@ -357,7 +374,6 @@ pub async fn install_version(
 
 pub async fn install_forge_or_neoforge(
     app: &AppHandle,
-    client: &reqwest::Client,
     game_version: &str,
     loader_type: &str,
     loader_version: &str,
@@ -365,6 +381,9 @@ pub async fn install_forge_or_neoforge(
     java_path: Option<String>,
     task_id: Option<String>
 ) -> Result<(), String> {
+    if loader_type == "forge" {
+        crate::forge_installer::check_supported(game_version)?;
+    }
     let _ = app.emit("download-progress", DownloadProgress {
         task_id: task_id.clone(),
         version_id: game_version.to_string(),
@@ -375,6 +394,10 @@ pub async fn install_forge_or_neoforge(
         current_file_progress: None,
         current_file_downloaded: None,
         current_file_total: None,
+        speed: None,
+        eta_seconds: None,
+        downloaded_bytes: None,
+        total_bytes: None,
     });
 
     let installer_url = if loader_type == "forge" {
@@ -390,129 +413,11 @@ pub async fn install_forge_or_neoforge(
 
     let temp_dir = std::env::temp_dir();
     let installer_path = temp_dir.join(format!("{}-{}-installer.jar", loader_type, loader_version));
+    download_files(app, game_version, vec![TaskFile::new(installer_url, installer_path.clone(), None)], task_id.clone()).await?;
 
-    let _ = app.emit("download-progress", DownloadProgress {
-        task_id: task_id.clone(),
-        version_id: game_version.to_string(),
-        total_files: 1,
-        downloaded_files: 0,
-        current_file: "Downloading installer...".to_string(),
-        percent: 0.0,
-        current_file_progress: None,
-        current_file_downloaded: None,
-        current_file_total: None,
-    });
-
-    let resp = client.get(&installer_url).send().await.map_err(|e| e.to_string())?;
-    if !resp.status().is_success() {
-        return Err(format!("Failed to download installer: {}", resp.status()));
-    }
-    let content = resp.bytes().await.map_err(|e| e.to_string())?;
-    fs::write(&installer_path, content).map_err(|e| e.to_string())?;
-
-    let _ = app.emit("download-progress", DownloadProgress {
-        task_id: task_id.clone(),
-        version_id: game_version.to_string(),
-        total_files: 1,
-        downloaded_files: 0,
-        current_file: "Running installer...".to_string(),
-        percent: 50.0,
-        current_file_progress: None,
-        current_file_downloaded: None,
-        current_file_total: None,
-    });
-
-    // Ensure launcher_profiles.json exists (required by Forge/NeoForge installer)
-    let profiles_path = mc_dir.join("launcher_profiles.json");
-    if !profiles_path.exists() {
-        let _ = app.emit("download-log", DownloadLog {
-            task_id: task_id.clone(),
-            message: "Creating dummy launcher_profiles.json...".to_string(),
-            level: "info".to_string(),
-        });
-        let content = r#"{"profiles":{}}"#;
-        fs::write(&profiles_path, content).map_err(|e| e.to_string())?;
-    }
-
-    let java = java_path.unwrap_or("java".to_string());
-    
-    // Run installer
-    let mc_dir_str = mc_dir.to_string_lossy().to_string();
-    
-    let _ = app.emit("download-log", DownloadLog {
-        task_id: task_id.clone(),
-        message: format!("Executing installer: {} -jar {} --installClient {}", java, installer_path.display(), mc_dir_str),
-        level: "info".to_string(),
-    });
-
-    let mut cmd = Command::new(&java);
-    cmd.arg("-jar")
-        .arg(&installer_path)
-        .arg("--installClient")
-        .arg(&mc_dir_str)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped());
-
-    // Ensure the installer targets the configured game directory (important on Windows).
-    if let Some(parent) = mc_dir.parent() {
-        if cfg!(target_os = "windows") {
-            cmd.env("APPDATA", parent);
-        } else {
-            cmd.env("HOME", parent);
-        }
-    }
-
-    let mut child = cmd
-        .spawn()
-        .map_err(|e| format!("Failed to start installer: {}", e))?;
-
-    if let Some(stdout) = child.stdout.take() {
-        let app_handle = app.clone();
-        let task_id = task_id.clone();
-        std::thread::spawn(move || {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    let _ = app_handle.emit("download-log", DownloadLog { task_id: task_id.clone(), message: line, level: "info".to_string() });
-                }
-            }
-        });
-    }
-
-    if let Some(stderr) = child.stderr.take() {
-        let app_handle = app.clone();
-        let task_id = task_id.clone();
-        std::thread::spawn(move || {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    let _ = app_handle.emit("download-log", DownloadLog { task_id: task_id.clone(), message: line, level: "error".to_string() });
-                }
-            }
-        });
-    }
-
-    let status = child.wait().map_err(|e| format!("Failed to wait for installer: {}", e))?;
-
-    if !status.success() {
-        return Err(format!("Installer failed with status: {}", status));
-    }
-
-    let _ = std::fs::remove_file(installer_path);
-
-    let _ = app.emit("download-progress", DownloadProgress {
-        task_id: task_id.clone(),
-        version_id: game_version.to_string(),
-        total_files: 1,
-        downloaded_files: 1,
-        current_file: "Installation Complete".to_string(),
-        percent: 100.0,
-        current_file_progress: None,
-        current_file_downloaded: None,
-        current_file_total: None,
-    });
-
-    Ok(())
+    let result = crate::forge_installer::install(app, &installer_path, game_version, mc_dir, java_path, task_id).await;
+    let _ = std::fs::remove_file(&installer_path);
+    result.map(|_| ())
 }
 
 #[tauri::command]
@@ -536,6 +441,10 @@ pub async fn download_single_file(app: AppHandle, url: String, path: String, tas
         current_file_progress: None,
         current_file_downloaded: None,
         current_file_total: None,
+        speed: None,
+        eta_seconds: None,
+        downloaded_bytes: None,
+        total_bytes: None,
     });
 
     let resp = client.get(&url).send().await.map_err(|e| e.to_string())?;
@@ -547,6 +456,9 @@ pub async fn download_single_file(app: AppHandle, url: String, path: String, tas
     let mut downloaded_bytes = 0u64;
     let mut file = fs::File::create(&path_buf).map_err(|e| e.to_string())?;
     let mut stream = resp.bytes_stream();
+    let manager = app.state::<crate::download_manager::DownloadManager>();
+    let started = std::time::Instant::now();
+    let mut last_emit: Option<std::time::Instant> = None;
 
     while let Some(chunk) = stream.next().await {
         if cancel_flag.load(Ordering::Relaxed) {
@@ -554,10 +466,17 @@ pub async fn download_single_file(app: AppHandle, url: String, path: String, tas
             return Err("Download cancelled".to_string());
         }
         let chunk = chunk.map_err(|e| e.to_string())?;
+        manager.throttle(chunk.len()).await;
         file.write_all(&chunk).map_err(|e| e.to_string())?;
         downloaded_bytes += chunk.len() as u64;
 
+        if last_emit.is_some_and(|t| t.elapsed() < crate::download_manager::PROGRESS_INTERVAL) {
+            continue;
+        }
+        last_emit = Some(std::time::Instant::now());
+
         let file_percent = total.map(|t| (downloaded_bytes as f64 / t as f64) * 100.0);
+        let speed = downloaded_bytes as f64 / started.elapsed().as_secs_f64().max(0.001);
         let _ = app.emit("download-progress", DownloadProgress {
             task_id: task_id.clone(),
             version_id: "SingleFile".to_string(),
@@ -568,6 +487,10 @@ pub async fn download_single_file(app: AppHandle, url: String, path: String, tas
             current_file_progress: file_percent,
             current_file_downloaded: Some(downloaded_bytes),
             current_file_total: total,
+            speed: Some(speed),
+            eta_seconds: total.map(|t| (t.saturating_sub(downloaded_bytes) as f64 / speed).ceil() as u64),
+            downloaded_bytes: Some(downloaded_bytes),
+            total_bytes: total,
         });
     }
 
@@ -581,6 +504,10 @@ pub async fn download_single_file(app: AppHandle, url: String, path: String, tas
         current_file_progress: Some(100.0),
         current_file_downloaded: total,
         current_file_total: total,
+        speed: None,
+        eta_seconds: None,
+        downloaded_bytes: Some(downloaded_bytes),
+        total_bytes: Some(downloaded_bytes),
     });
 
     app.state::<DownloadState>().clear_cancel(&cancel_key);
@@ -620,7 +547,7 @@ pub async fn download_custom_files(
         } else {
              mc_dir.join(&file.path)
         };
-        queue.push((file.url, path, file.token));
+        queue.push(TaskFile::new(file.url, path, file.token));
     }
 
     download_files(&app, &version_id, queue, task_id).await?;
@@ -628,41 +555,34 @@ pub async fn download_custom_files(
     Ok(())
 }
 
-pub async fn prepare_vanilla_downloads(client: &reqwest::Client, version_id: &str, mc_dir: &PathBuf) -> Result<(Vec<(String, PathBuf, Option<String>)>, VersionDetails), String> {
-    let manifest: VersionManifest = client
-        .get(MANIFEST_URL)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .json()
-        .await
-        .map_err(|e| e.to_string())?;
+pub async fn prepare_vanilla_downloads(app: &AppHandle, client: &reqwest::Client, version_id: &str, mc_dir: &PathBuf) -> Result<(Vec<TaskFile>, VersionDetails), String> {
+    let manifest: VersionManifest = crate::http_cache::get_json(app, client, MANIFEST_URL).await?;
 
     let version_entry = manifest.versions.iter().find(|v| v.id == version_id)
         .ok_or("Version not found")?;
 
-    let details: VersionDetails = client
-        .get(&version_entry.url)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?
-        .json()
-        .await
-        .map_err(|e| e.to_string())?;
+    let version_json = crate::http_cache::get_text(app, client, &version_entry.url).await?;
 
-    // Save version json
+    prepare_version_downloads(app, client, version_id, &version_json, mc_dir).await
+}
+
+pub async fn prepare_version_downloads(app: &AppHandle, client: &reqwest::Client, version_id: &str, version_json: &str, mc_dir: &PathBuf) -> Result<(Vec<TaskFile>, VersionDetails), String> {
+    let details: VersionDetails = serde_json::from_str(version_json).map_err(|e| format!("Invalid version json for {}: {}", version_id, e))?;
+
+    // Save the version json as published, so fields we don't model (javaVersion, logging...) reach the launcher
     let version_json_path = mc_dir.join("versions").join(version_id).join(format!("{}.json", version_id));
     if let Some(parent) = version_json_path.parent() {
         fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
-    let json_content = serde_json::to_string_pretty(&details).map_err(|e| e.to_string())?;
-    fs::write(&version_json_path, json_content).map_err(|e| e.to_string())?;
+    fs::write(&version_json_path, version_json).map_err(|e| e.to_string())?;
 
     let mut queue = Vec::new();
 
     // Client Jar
-    let client_jar_path = mc_dir.join("versions").join(version_id).join(format!("{}.jar", version_id));
-    queue.push((details.downloads.client.url.clone(), client_jar_path, None));
+    if let Some(downloads) = &details.downloads {
+        let client_jar_path = mc_dir.join("versions").join(version_id).join(format!("{}.jar", version_id));
+        queue.push(TaskFile::new(downloads.client.url.clone(), client_jar_path, None).with_sha1(downloads.client.sha1.clone()));
+    }
 
     // Libraries
     for lib in &details.libraries {
@@ -671,7 +591,7 @@ pub async fn prepare_vanilla_downloads(client: &reqwest::Client, version_id: &st
                 if let Some(artifact) = &downloads.artifact {
                     let path = artifact.path.as_ref().ok_or("Library path missing")?;
                     let full_path = mc_dir.join("libraries").join(path);
-                    queue.push((artifact.url.clone(), full_path, None));
+                    queue.push(TaskFile::new(artifact.url.clone(), full_path, None).with_sha1(artifact.sha1.clone()));
                 }
                 // Natives
                 if let Some(classifiers) = &downloads.classifiers {
@@ -682,10 +602,15 @@ pub async fn prepare_vanilla_downloads(client: &reqwest::Client, version_id: &st
                     } else {
                         "natives-linux"
                     };
-                    if let Some(native_info) = classifiers.get(os_key) {
+                    // Older versions name the classifier through "natives", e.g. "natives-windows-${arch}"
+                    let os_name = if cfg!(target_os = "windows") { "windows" } else if cfg!(target_os = "macos") { "osx" } else { "linux" };
+                    let arch = if cfg!(target_arch = "x86") { "32" } else { "64" };
+                    let mapped_key = lib.natives.as_ref().and_then(|n| n.get(os_name)).map(|key| key.replace("${arch}", arch));
+                    let native_info = mapped_key.as_deref().and_then(|key| classifiers.get(key)).or_else(|| classifiers.get(os_key));
+                    if let Some(native_info) = native_info {
                          let path = native_info.path.as_ref().ok_or("Native path missing")?;
                          let full_path = mc_dir.join("libraries").join(path);
-                         queue.push((native_info.url.clone(), full_path, None));
+                         queue.push(TaskFile::new(native_info.url.clone(), full_path, None).with_sha1(native_info.sha1.clone()));
                     }
                 }
             } else {
@@ -702,7 +627,7 @@ pub async fn prepare_vanilla_downloads(client: &reqwest::Client, version_id: &st
                     // Use BMCLAPI or official repo? Default to official if url not present
                     let base_url = lib.url.as_deref().unwrap_or("https://libraries.minecraft.net/");
                     let url = format!("{}{}/{}/{}/{}-{}.jar", base_url, group, artifact, version, artifact, version);
-                    queue.push((url, full_path, None));
+                    queue.push(TaskFile::new(url, full_path, None));
                 }
             }
         }
@@ -714,7 +639,7 @@ pub async fn prepare_vanilla_downloads(client: &reqwest::Client, version_id: &st
         fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
     // Download asset index
-    let asset_index_content = client.get(&details.asset_index.url).send().await.map_err(|e| e.to_string())?.text().await.map_err(|e| e.to_string())?;
+    let asset_index_content = crate::http_cache::get_text(app, client, &details.asset_index.url).await?;
     fs::write(&asset_index_path, &asset_index_content).map_err(|e| e.to_string())?;
 
     let assets: Assets = serde_json::from_str(&asset_index_content).map_err(|e| e.to_string())?;
@@ -722,18 +647,80 @@ pub async fn prepare_vanilla_downloads(client: &reqwest::Client, version_id: &st
         let hash_head = &object.hash[0..2];
         let path = mc_dir.join("assets").join("objects").join(hash_head).join(&object.hash);
         let url = format!("https://resources.download.minecraft.net/{}/{}", hash_head, object.hash);
-        queue.push((url, path, None));
+        queue.push(TaskFile::new(url, path, None).with_sha1(Some(object.hash)));
     }
 
     Ok((queue, details))
 }
 
-pub async fn prepare_fabric_downloads(client: &reqwest::Client, game_version: &str, loader_version: &str, mc_dir: &PathBuf) -> Result<(Vec<(String, PathBuf, Option<String>)>, String), String> {
+// Ids from archives end up as a directory under versions/, so they must be a single plain name
+fn is_plain_version_id(id: &str) -> bool {
+    !id.is_empty()
+        && id != "."
+        && id != ".."
+        && !id.contains(['/', '\\', ':'])
+        && !id.chars().any(|c| c.is_control())
+}
+
+// Experimental snapshots are published as zips holding "<id>/<id>.json" next to "<id>/<id>.jar"
+pub fn find_version_archive(archive: &zip::ZipArchive<fs::File>) -> Option<String> {
+    let names: Vec<&str> = archive.file_names().collect();
+    names.iter().find_map(|name| {
+        let (dir, file) = name.split_once('/')?;
+        if !is_plain_version_id(dir) {
+            return None;
+        }
+        let is_version = file == format!("{}.json", dir) && names.contains(&format!("{}/{}.jar", dir, dir).as_str());
+        is_version.then(|| dir.to_string())
+    })
+}
+
+pub async fn install_version_archive(
+    app: &AppHandle,
+    archive: &mut zip::ZipArchive<fs::File>,
+    id: &str,
+    mc_dir: &PathBuf,
+    task_id: Option<String>
+) -> Result<(), String> {
+    if !is_plain_version_id(id) {
+        return Err(format!("Invalid version id in archive: {}", id));
+    }
+    let mut version_json = String::new();
+    archive
+        .by_name(&format!("{}/{}.json", id, id))
+        .map_err(|e| e.to_string())?
+        .read_to_string(&mut version_json)
+        .map_err(|e| e.to_string())?;
+
+    let version_dir = mc_dir.join("versions").join(id);
+    fs::create_dir_all(&version_dir).map_err(|e| e.to_string())?;
+    {
+        let mut jar = archive.by_name(&format!("{}/{}.jar", id, id)).map_err(|e| e.to_string())?;
+        let mut out = fs::File::create(version_dir.join(format!("{}.jar", id))).map_err(|e| e.to_string())?;
+        std::io::copy(&mut jar, &mut out).map_err(|e| e.to_string())?;
+    }
+
+    let client = reqwest::Client::new();
+    let (queue, _) = prepare_version_downloads(app, &client, id, &version_json, mc_dir).await?;
+    download_files(app, id, queue, task_id).await
+}
+
+pub async fn prepare_fabric_downloads(app: &AppHandle, client: &reqwest::Client, game_version: &str, loader_version: &str, mc_dir: &PathBuf) -> Result<(Vec<TaskFile>, String), String> {
     let url = format!("https://meta.fabricmc.net/v2/versions/loader/{}/{}/profile/json", game_version, loader_version);
-    let profile_json: serde_json::Value = client.get(&url).send().await.map_err(|e| e.to_string())?.json().await.map_err(|e| e.to_string())?;
+    prepare_profile_downloads(app, client, &url, "https://maven.fabricmc.net/", mc_dir).await
+}
 
-    // Save fabric profile json
-    // The ID usually looks like "fabric-loader-0.14.21-1.20.1"
+// Quilt's meta server serves launcher profiles in the same shape as Fabric's
+pub async fn prepare_quilt_downloads(app: &AppHandle, client: &reqwest::Client, game_version: &str, loader_version: &str, mc_dir: &PathBuf) -> Result<(Vec<TaskFile>, String), String> {
+    let url = format!("https://meta.quiltmc.org/v3/versions/loader/{}/{}/profile/json", game_version, loader_version);
+    prepare_profile_downloads(app, client, &url, "https://maven.quiltmc.org/repository/release/", mc_dir).await
+}
+
+async fn prepare_profile_downloads(app: &AppHandle, client: &reqwest::Client, url: &str, default_maven: &str, mc_dir: &PathBuf) -> Result<(Vec<TaskFile>, String), String> {
+    let profile_json: serde_json::Value = crate::http_cache::get_json(app, client, url).await?;
+
+    // Save the loader profile json
+    // The ID usually looks like "fabric-loader-0.14.21-1.20.1" or "quilt-loader-0.26.0-1.20.1"
     let version_id = profile_json["id"].as_str().ok_or("Invalid profile json")?;
     let version_json_path = mc_dir.join("versions").join(version_id).join(format!("{}.json", version_id));
     if let Some(parent) = version_json_path.parent() {
@@ -755,9 +742,10 @@ pub async fn prepare_fabric_downloads(client: &reqwest::Client, game_version: &s
                 let path = format!("{}/{}/{}/{}-{}.jar", group, artifact, version, artifact, version);
                 let full_path = mc_dir.join("libraries").join(path);
                 
-                let base_url = lib["url"].as_str().unwrap_or("https://maven.fabricmc.net/");
-                let url = format!("{}{}/{}/{}/{}-{}.jar", base_url, group, artifact, version, artifact, version);
-                queue.push((url, full_path, None));
+                let base_url = lib["url"].as_str().unwrap_or(default_maven).trim_end_matches('/');
+                let url = format!("{}/{}/{}/{}/{}-{}.jar", base_url, group, artifact, version, artifact, version);
+                let sha1 = lib["sha1"].as_str().map(|s| s.to_string());
+                queue.push(TaskFile::new(url, full_path, None).with_sha1(sha1));
             }
         }
     }
@@ -765,188 +753,11 @@ pub async fn prepare_fabric_downloads(client: &reqwest::Client, game_version: &s
     Ok((queue, version_id.to_string()))
 }
 
-pub async fn download_files(app: &AppHandle, version_id: &str, queue: Vec<(String, PathBuf, Option<String>)>, task_id: Option<String>) -> Result<(), String> {
-    const MAX_ATTEMPTS: u32 = 3;
-    const BASE_DELAY_MS: u64 = 500;
-
-    let total_files = queue.len();
-    let semaphore = Arc::new(Semaphore::new(10)); // Concurrency limit
-    let client = reqwest::Client::new();
-    let completed = Arc::new(Mutex::new(0usize));
-    let cancel_key = get_cancel_key(version_id, &task_id);
-    let cancel_flag = app.state::<DownloadState>().get_cancel_flag(&cancel_key);
-
-    let mut stream = futures::stream::iter(queue)
-        .map(|(url, path, token)| {
-            let client = client.clone();
-            let semaphore = semaphore.clone();
-            let app = app.clone();
-            let task_id = task_id.clone();
-            let completed = completed.clone();
-            let cancel_flag = cancel_flag.clone();
-            async move {
-                let _permit = semaphore.acquire().await.unwrap();
-                let filename = path.file_name().unwrap_or_default().to_string_lossy().to_string();
-                if cancel_flag.load(Ordering::Relaxed) {
-                    return Err("Download cancelled".to_string());
-                }
-                if path.exists() {
-                    let mut completed_count = completed.lock().map_err(|e| e.to_string())?;
-                    *completed_count += 1;
-                    let overall_percent = if total_files == 0 {
-                        0.0
-                    } else {
-                        (*completed_count as f64 / total_files as f64) * 100.0
-                    };
-                    let _ = app.emit("download-progress", DownloadProgress {
-                        task_id: task_id.clone(),
-                        version_id: version_id.to_string(),
-                        total_files,
-                        downloaded_files: *completed_count,
-                        current_file: filename.clone(),
-                        percent: overall_percent,
-                        current_file_progress: None,
-                        current_file_downloaded: None,
-                        current_file_total: None,
-                    });
-                    let _ = app.emit("download-file-progress", DownloadFileProgress {
-                        task_id: task_id.clone(),
-                        filename: filename.clone(),
-                        progress: 100.0,
-                        status: "cached".to_string(),
-                    });
-                    return Ok(());
-                }
-                if let Some(parent) = path.parent() {
-                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
-                }
-                
-                let _ = app.emit("download-log", DownloadLog { task_id: task_id.clone(), message: format!("Downloading {}", filename), level: "info".to_string() });
-
-                let mut last_error: Option<String> = None;
-
-                for attempt in 1..=MAX_ATTEMPTS {
-                    let mut req = client.get(&url);
-                    if let Some(t) = token.clone() {
-                        req = req.header("Authorization", format!("Bearer {}", t));
-                    }
-
-                    let result: Result<(), String> = async {
-                        let resp = req.send().await.map_err(|e| e.to_string())?;
-                        if !resp.status().is_success() {
-                            return Err(format!("HTTP {}", resp.status()));
-                        }
-                        let total = resp.content_length();
-                        let mut downloaded_bytes = 0u64;
-                        let mut file = fs::File::create(&path).map_err(|e| e.to_string())?;
-                        let mut stream = resp.bytes_stream();
-
-                        while let Some(chunk) = stream.next().await {
-                            if cancel_flag.load(Ordering::Relaxed) {
-                                return Err("Download cancelled".to_string());
-                            }
-                            let chunk = chunk.map_err(|e| e.to_string())?;
-                            file.write_all(&chunk).map_err(|e| e.to_string())?;
-                            downloaded_bytes += chunk.len() as u64;
-
-                            let file_percent = total.map(|t| (downloaded_bytes as f64 / t as f64) * 100.0);
-                            let completed_count = completed.lock().map_err(|e| e.to_string())?;
-                            let overall_percent = if total_files == 0 {
-                                0.0
-                            } else {
-                                (*completed_count as f64 / total_files as f64) * 100.0
-                            };
-                            let _ = app.emit("download-progress", DownloadProgress {
-                                task_id: task_id.clone(),
-                                version_id: version_id.to_string(),
-                                total_files,
-                                downloaded_files: *completed_count,
-                                current_file: filename.clone(),
-                                percent: overall_percent,
-                                current_file_progress: file_percent,
-                                current_file_downloaded: Some(downloaded_bytes),
-                                current_file_total: total,
-                            });
-                            let _ = app.emit("download-file-progress", DownloadFileProgress {
-                                task_id: task_id.clone(),
-                                filename: filename.clone(),
-                                progress: file_percent.unwrap_or(0.0),
-                                status: "downloading".to_string(),
-                            });
-                        }
-                        Ok(())
-                    }
-                    .await;
-
-                    match result {
-                        Ok(()) => {
-                            let mut completed_count = completed.lock().map_err(|e| e.to_string())?;
-                            *completed_count += 1;
-                            let overall_percent = if total_files == 0 {
-                                0.0
-                            } else {
-                                (*completed_count as f64 / total_files as f64) * 100.0
-                            };
-                            let _ = app.emit("download-progress", DownloadProgress {
-                                task_id: task_id.clone(),
-                                version_id: version_id.to_string(),
-                                total_files,
-                                downloaded_files: *completed_count,
-                                current_file: filename.clone(),
-                                percent: overall_percent,
-                                current_file_progress: Some(100.0),
-                                current_file_downloaded: None,
-                                current_file_total: None,
-                            });
-                            let _ = app.emit("download-file-progress", DownloadFileProgress {
-                                task_id: task_id.clone(),
-                                filename: filename.clone(),
-                                progress: 100.0,
-                                status: "done".to_string(),
-                            });
-                            return Ok::<(), String>(());
-                        },
-                        Err(e) => {
-                            last_error = Some(e);
-                            if attempt < MAX_ATTEMPTS {
-                                let delay = BASE_DELAY_MS * 2u64.pow(attempt - 1);
-                                let _ = app.emit(
-                                    "download-log",
-                                    DownloadLog {
-                                        task_id: task_id.clone(),
-                                        message: format!(
-                                            "Retry {}/{} for {} in {}ms",
-                                            attempt,
-                                            MAX_ATTEMPTS,
-                                            filename,
-                                            delay
-                                        ),
-                                        level: "warn".to_string(),
-                                    },
-                                );
-                                sleep(Duration::from_millis(delay)).await;
-                            }
-                        }
-                    }
-                }
-
-                Err(last_error.unwrap_or_else(|| "Download failed".to_string()))
-            }
-        })
-        .buffer_unordered(10);
-
-    while let Some(result) = stream.next().await {
-        if let Err(e) = result {
-            println!("Download error: {}", e);
-            let _ = app.emit("download-log", DownloadLog { task_id: task_id.clone(), message: format!("Download error: {}", e), level: "error".to_string() });
-            // Continue or fail? For now, log and continue, but maybe should fail?
-            // Ideally we retry.
-        }
-        if cancel_flag.load(Ordering::Relaxed) {
-            app.state::<DownloadState>().clear_cancel(&cancel_key);
-            return Err("Download cancelled".to_string());
-        }
-    }
-    app.state::<DownloadState>().clear_cancel(&cancel_key);
-    Ok(())
+pub async fn download_files(app: &AppHandle, version_id: &str, queue: Vec<TaskFile>, task_id: Option<String>) -> Result<(), String> {
+    let id = get_cancel_key(version_id, &task_id);
+    let cancel_flag = app.state::<DownloadState>().get_cancel_flag(&id);
+    let done = app
+        .state::<crate::download_manager::DownloadManager>()
+        .enqueue(app, id, task_id, version_id, queue, cancel_flag)?;
+    done.await.map_err(|_| "Download task was dropped".to_string())?
 }
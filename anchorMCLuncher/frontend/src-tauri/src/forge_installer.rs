@@ -0,0 +1,457 @@
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{AppHandle, Emitter};
+
+use crate::content_store::file_sha1;
+use crate::download_manager::TaskFile;
+use crate::downloader::{DownloadLog, DownloadProgress};
+
+const DEFAULT_LIBRARY_URL: &str = "https://libraries.minecraft.net/";
+const LEGACY_FORGE_MAVEN: &str = "https://maven.minecraftforge.net/";
+const CLASSPATH_SEPARATOR: &str = if cfg!(target_os = "windows") { ";" } else { ":" };
+
+// "group:artifact:version[:classifier][@extension]" -> path relative to a maven repository
+pub fn maven_path(coords: &str) -> Option<String> {
+    let (coords, extension) = coords.split_once('@').unwrap_or((coords, "jar"));
+    let parts: Vec<&str> = coords.split(':').collect();
+    if parts.len() < 3 {
+        return None;
+    }
+    let (group, artifact, version) = (parts[0].replace('.', "/"), parts[1], parts[2]);
+    let file = match parts.get(3) {
+        Some(classifier) => format!("{}-{}-{}.{}", artifact, version, classifier, extension),
+        None => format!("{}-{}.{}", artifact, version, extension),
+    };
+    Some(format!("{}/{}/{}/{}", group, artifact, version, file))
+}
+
+pub struct InstallerJar {
+    archive: zip::ZipArchive<fs::File>,
+}
+
+impl InstallerJar {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = fs::File::open(path).map_err(|e| e.to_string())?;
+        let archive = zip::ZipArchive::new(file).map_err(|e| format!("Invalid installer jar: {}", e))?;
+        Ok(Self { archive })
+    }
+
+    pub fn read(&mut self, name: &str) -> Result<Vec<u8>, String> {
+        let mut entry = self
+            .archive
+            .by_name(name.trim_start_matches('/'))
+            .map_err(|_| format!("{} is missing from the installer", name))?;
+        let mut buf = Vec::new();
+        entry.read_to_end(&mut buf).map_err(|e| e.to_string())?;
+        Ok(buf)
+    }
+
+    pub fn read_json(&mut self, name: &str) -> Result<Value, String> {
+        serde_json::from_slice(&self.read(name)?).map_err(|e| format!("Invalid {} in installer: {}", name, e))
+    }
+
+    pub fn has(&mut self, name: &str) -> bool {
+        self.archive.by_name(name.trim_start_matches('/')).is_ok()
+    }
+
+    pub fn extract(&mut self, name: &str, target: &Path) -> Result<(), String> {
+        let data = self.read(name)?;
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::write(target, data).map_err(|e| e.to_string())
+    }
+}
+
+pub fn log(app: &AppHandle, task_id: &Option<String>, level: &str, message: String) {
+    let _ = app.emit("download-log", DownloadLog { task_id: task_id.clone(), message, level: level.to_string() });
+}
+
+pub fn status(app: &AppHandle, task_id: &Option<String>, version_id: &str, message: String, done: usize, total: usize) {
+    let _ = app.emit("download-progress", DownloadProgress {
+        task_id: task_id.clone(),
+        version_id: version_id.to_string(),
+        total_files: total,
+        downloaded_files: done,
+        current_file: message,
+        percent: if total == 0 { 0.0 } else { (done as f64 / total as f64) * 100.0 },
+        current_file_progress: None,
+        current_file_downloaded: None,
+        current_file_total: None,
+        speed: None,
+        eta_seconds: None,
+        downloaded_bytes: None,
+        total_bytes: None,
+    });
+}
+
+pub fn write_version_json(mc_dir: &Path, json: &Value) -> Result<String, String> {
+    let id = json["id"].as_str().ok_or("Installer version json has no id")?;
+    let path = mc_dir.join("versions").join(id).join(format!("{}.json", id));
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(json).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    Ok(id.to_string())
+}
+
+// Collects the download for every library; artifacts without a URL ship inside the installer under maven/
+pub fn library_downloads(installer: &mut InstallerJar, libraries: &[Value], mc_dir: &Path, seen: &mut HashSet<String>) -> Result<Vec<TaskFile>, String> {
+    let libraries_dir = mc_dir.join("libraries");
+    let mut queue = Vec::new();
+    for lib in libraries {
+        let name = lib["name"].as_str().unwrap_or_default();
+        let artifact = &lib["downloads"]["artifact"];
+        let (path, url, sha1) = if artifact.is_object() {
+            let path = artifact["path"].as_str().map(|p| p.to_string()).or_else(|| maven_path(name));
+            (path, artifact["url"].as_str().unwrap_or_default().to_string(), artifact["sha1"].as_str().map(|s| s.to_string()))
+        } else {
+            let path = maven_path(name);
+            let base = lib["url"].as_str().unwrap_or(DEFAULT_LIBRARY_URL).trim_end_matches('/');
+            let url = path.as_ref().map(|p| format!("{}/{}", base, p)).unwrap_or_default();
+            (path, url, None)
+        };
+        let Some(path) = path else {
+            continue;
+        };
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+
+        let target = libraries_dir.join(&path);
+        let bundled = format!("maven/{}", path);
+        if installer.has(&bundled) {
+            if !target.exists() {
+                installer.extract(&bundled, &target)?;
+            }
+        } else if !url.is_empty() {
+            queue.push(TaskFile::new(url, target, None).with_sha1(sha1));
+        }
+        // Anything else has no URL because the processors produce it
+    }
+    Ok(queue)
+}
+
+struct Processor {
+    jar: String,
+    classpath: Vec<String>,
+    args: Vec<String>,
+    outputs: Vec<(String, String)>,
+}
+
+fn library_file(libraries_dir: &Path, coords: &str) -> Result<PathBuf, String> {
+    maven_path(coords)
+        .map(|p| libraries_dir.join(p))
+        .ok_or_else(|| format!("Invalid artifact {}", coords))
+}
+
+// Mirrors the installer's token rules: [artifact] is a library path, {KEY} a data entry, 'text' a literal
+fn substitute(value: &str, data: &HashMap<String, String>, libraries_dir: &Path) -> Result<String, String> {
+    if let Some(coords) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return Ok(library_file(libraries_dir, coords)?.to_string_lossy().to_string());
+    }
+    if let Some(literal) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return Ok(literal.to_string());
+    }
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..].find('}').map(|e| start + e).ok_or_else(|| format!("Unclosed token in {}", value))?;
+        let key = &rest[start + 1..end];
+        out.push_str(data.get(key).ok_or_else(|| format!("Unknown installer data {}", key))?);
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn jar_main_class(jar: &Path) -> Result<String, String> {
+    let file = fs::File::open(jar).map_err(|e| format!("Processor {} missing: {}", jar.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let mut manifest = String::new();
+    archive
+        .by_name("META-INF/MANIFEST.MF")
+        .map_err(|e| e.to_string())?
+        .read_to_string(&mut manifest)
+        .map_err(|e| e.to_string())?;
+    manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("Main-Class:"))
+        .map(|class| class.trim().to_string())
+        .ok_or_else(|| format!("{} has no Main-Class", jar.display()))
+}
+
+fn outputs_valid(outputs: &[(String, String)]) -> bool {
+    !outputs.is_empty()
+        && outputs
+            .iter()
+            .all(|(path, sha1)| file_sha1(Path::new(path)).is_ok_and(|actual| actual.eq_ignore_ascii_case(sha1)))
+}
+
+// Forge before 1.6 has no installer, only a universal jar to be merged into the game jar
+pub fn check_supported(game_version: &str) -> Result<(), String> {
+    let mut parts = game_version.split('.').map(|part| part.parse::<u32>());
+    if let (Some(Ok(1)), Some(Ok(minor))) = (parts.next(), parts.next()) {
+        if minor < 6 {
+            return Err(format!("Forge for Minecraft {} is only available as a jar mod, which is not supported before 1.6", game_version));
+        }
+    }
+    Ok(())
+}
+
+// Installers before 1.13 carry the version json inline as "versionInfo" and have no processors
+pub async fn install(
+    app: &AppHandle,
+    installer_path: &Path,
+    game_version: &str,
+    mc_dir: &Path,
+    java_path: Option<String>,
+    task_id: Option<String>
+) -> Result<String, String> {
+    let profile = {
+        let mut installer = InstallerJar::open(installer_path)?;
+        if !installer.has("install_profile.json") {
+            return Err(format!("{} is not a Forge installer; universal-only builds are not supported", installer_path.display()));
+        }
+        installer.read_json("install_profile.json")?
+    };
+    if profile.get("versionInfo").is_some() {
+        install_legacy(app, installer_path, game_version, mc_dir, task_id).await
+    } else {
+        install_modern(app, installer_path, game_version, mc_dir, java_path, task_id).await
+    }
+}
+
+// Legacy installers only need the universal jar copied into libraries and the remaining libraries downloaded
+async fn install_legacy(
+    app: &AppHandle,
+    installer_path: &Path,
+    game_version: &str,
+    mc_dir: &Path,
+    task_id: Option<String>
+) -> Result<String, String> {
+    let mut installer = InstallerJar::open(installer_path)?;
+    let profile = installer.read_json("install_profile.json")?;
+    let install = &profile["install"];
+    let mut version_json = profile["versionInfo"].clone();
+    let minecraft = install["minecraft"].as_str().unwrap_or(game_version).to_string();
+
+    if let Some(target) = install["target"].as_str() {
+        version_json["id"] = Value::String(target.to_string());
+    }
+    // 1.6 era profiles repeat the whole vanilla json instead of inheriting it
+    let parent_libraries = if version_json.get("inheritsFrom").is_none() {
+        version_json["inheritsFrom"] = Value::String(minecraft.clone());
+        read_version_json(mc_dir, &minecraft)?["libraries"]
+            .as_array()
+            .map(|libs| libs.iter().filter_map(|lib| artifact_key(lib["name"].as_str()?)).collect())
+            .unwrap_or_default()
+    } else {
+        HashSet::new()
+    };
+    if version_json.get("jar").is_none() {
+        version_json["jar"] = Value::String(minecraft.clone());
+    }
+
+    let libraries: Vec<Value> = version_json["libraries"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|lib| lib["clientreq"].as_bool() != Some(false))
+        .filter(|lib| lib["name"].as_str().and_then(artifact_key).is_none_or(|key| !parent_libraries.contains(&key)))
+        .map(|mut lib| {
+            // files.minecraftforge.net no longer serves maven artifacts
+            if let Some(url) = lib["url"].as_str() {
+                if url.contains("files.minecraftforge.net/maven") {
+                    lib["url"] = Value::String(LEGACY_FORGE_MAVEN.to_string());
+                }
+            }
+            lib
+        })
+        .collect();
+    version_json["libraries"] = Value::Array(libraries.clone());
+    let version_id = write_version_json(mc_dir, &version_json)?;
+
+    let mut seen = HashSet::new();
+    if let (Some(file_path), Some(coords)) = (install["filePath"].as_str(), install["path"].as_str()) {
+        let path = maven_path(coords).ok_or_else(|| format!("Invalid artifact {}", coords))?;
+        log(app, &task_id, "info", format!("Extracting {}", file_path));
+        installer.extract(file_path, &mc_dir.join("libraries").join(&path))?;
+        seen.insert(path);
+    }
+
+    let queue = library_downloads(&mut installer, &libraries, mc_dir, &mut seen)?;
+    log(app, &task_id, "info", format!("Downloading {} libraries for {}", queue.len(), version_id));
+    crate::downloader::download_files(app, game_version, queue, task_id.clone()).await?;
+    status(app, &task_id, &version_id, "Installation Complete".to_string(), 1, 1);
+    Ok(version_id)
+}
+
+pub fn read_version_json(mc_dir: &Path, id: &str) -> Result<Value, String> {
+    let path = mc_dir.join("versions").join(id).join(format!("{}.json", id));
+    let content = fs::read_to_string(&path).map_err(|e| format!("Minecraft {} must be installed first: {}", id, e))?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+// "group:artifact" plus classifier, so different versions of the same library compare equal
+fn artifact_key(name: &str) -> Option<String> {
+    let parts: Vec<&str> = name.split(':').collect();
+    if parts.len() < 3 {
+        return None;
+    }
+    Some(match parts.get(3) {
+        Some(classifier) => format!("{}:{}:{}", parts[0], parts[1], classifier),
+        None => format!("{}:{}", parts[0], parts[1]),
+    })
+}
+
+// Installs a 1.13+ installer: version json, libraries through the download queue, then the client processors
+async fn install_modern(
+    app: &AppHandle,
+    installer_path: &Path,
+    game_version: &str,
+    mc_dir: &Path,
+    java_path: Option<String>,
+    task_id: Option<String>
+) -> Result<String, String> {
+    let mut installer = InstallerJar::open(installer_path)?;
+    let profile = installer.read_json("install_profile.json")?;
+    let version_json_name = profile["json"].as_str().unwrap_or("/version.json").to_string();
+    let version_json = installer.read_json(&version_json_name)?;
+    let version_id = write_version_json(mc_dir, &version_json)?;
+
+    let mut seen = HashSet::new();
+    let mut queue = Vec::new();
+    for libraries in [&version_json["libraries"], &profile["libraries"]] {
+        if let Some(libraries) = libraries.as_array() {
+            queue.extend(library_downloads(&mut installer, libraries, mc_dir, &mut seen)?);
+        }
+    }
+    log(app, &task_id, "info", format!("Downloading {} libraries for {}", queue.len(), version_id));
+    crate::downloader::download_files(app, game_version, queue, task_id.clone()).await?;
+
+    let libraries_dir = mc_dir.join("libraries");
+    let work_dir = std::env::temp_dir().join(format!("amcl-installer-{}", uuid::Uuid::new_v4()));
+    let minecraft = profile["minecraft"].as_str().unwrap_or(game_version);
+
+    let mut data: HashMap<String, String> = HashMap::new();
+    data.insert("SIDE".to_string(), "client".to_string());
+    data.insert("MINECRAFT_VERSION".to_string(), minecraft.to_string());
+    data.insert(
+        "MINECRAFT_JAR".to_string(),
+        mc_dir.join("versions").join(minecraft).join(format!("{}.jar", minecraft)).to_string_lossy().to_string(),
+    );
+    data.insert("ROOT".to_string(), mc_dir.to_string_lossy().to_string());
+    data.insert("INSTALLER".to_string(), installer_path.to_string_lossy().to_string());
+    data.insert("LIBRARY_DIR".to_string(), libraries_dir.to_string_lossy().to_string());
+    if let Some(entries) = profile["data"].as_object() {
+        for (key, value) in entries {
+            let Some(value) = value["client"].as_str() else {
+                continue;
+            };
+            let resolved = if value.starts_with('/') {
+                let target = work_dir.join(value.trim_start_matches('/'));
+                installer.extract(value, &target)?;
+                target.to_string_lossy().to_string()
+            } else {
+                substitute(value, &HashMap::new(), &libraries_dir).unwrap_or_else(|_| value.to_string())
+            };
+            data.insert(key.clone(), resolved);
+        }
+    }
+
+    let mut processors = Vec::new();
+    for processor in profile["processors"].as_array().cloned().unwrap_or_default() {
+        let client_side = processor["sides"]
+            .as_array()
+            .is_none_or(|sides| sides.iter().any(|s| s.as_str() == Some("client")));
+        if !client_side {
+            continue;
+        }
+        let strings = |field: &str| -> Vec<String> {
+            processor[field]
+                .as_array()
+                .map(|items| items.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+                .unwrap_or_default()
+        };
+        let mut outputs = Vec::new();
+        if let Some(map) = processor["outputs"].as_object() {
+            for (path, sha1) in map {
+                outputs.push((
+                    substitute(path, &data, &libraries_dir)?,
+                    substitute(sha1.as_str().unwrap_or_default(), &data, &libraries_dir)?,
+                ));
+            }
+        }
+        processors.push(Processor {
+            jar: processor["jar"].as_str().unwrap_or_default().to_string(),
+            classpath: strings("classpath"),
+            args: strings("args")
+                .iter()
+                .map(|arg| substitute(arg, &data, &libraries_dir))
+                .collect::<Result<_, _>>()?,
+            outputs,
+        });
+    }
+
+    // Processors target the game's own Java, which may not have been needed (or downloaded) before
+    let java = crate::launcher::java_for_version(app, mc_dir, minecraft, java_path.as_deref()).await?;
+    let total = processors.len();
+    let app_handle = app.clone();
+    let status_version = version_id.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || -> Result<(), String> {
+        for (i, processor) in processors.iter().enumerate() {
+            status(&app_handle, &task_id, &status_version, format!("Running processor {}/{}: {}", i + 1, total, processor.jar), i, total);
+            if outputs_valid(&processor.outputs) {
+                log(&app_handle, &task_id, "info", format!("Outputs of {} are up to date", processor.jar));
+                continue;
+            }
+
+            let jar = library_file(&libraries_dir, &processor.jar)?;
+            let main_class = jar_main_class(&jar)?;
+            let mut classpath = vec![jar.to_string_lossy().to_string()];
+            for coords in &processor.classpath {
+                classpath.push(library_file(&libraries_dir, coords)?.to_string_lossy().to_string());
+            }
+
+            let output = Command::new(&java)
+                .arg("-cp")
+                .arg(classpath.join(CLASSPATH_SEPARATOR))
+                .arg(&main_class)
+                .args(&processor.args)
+                .output()
+                .map_err(|e| format!("Failed to start processor {}: {}", processor.jar, e))?;
+            for line in String::from_utf8_lossy(&output.stdout).lines() {
+                log(&app_handle, &task_id, "info", line.to_string());
+            }
+            for line in String::from_utf8_lossy(&output.stderr).lines() {
+                log(&app_handle, &task_id, "error", line.to_string());
+            }
+            if !output.status.success() {
+                return Err(format!("Processor {} failed with status: {}", processor.jar, output.status));
+            }
+
+            for (path, expected) in &processor.outputs {
+                let actual = file_sha1(Path::new(path)).map_err(|e| format!("Processor output {} missing: {}", path, e))?;
+                if !actual.eq_ignore_ascii_case(expected) {
+                    let _ = fs::remove_file(path);
+                    return Err(format!("Processor output {} has sha1 {}, expected {}", path, actual, expected));
+                }
+            }
+        }
+        status(&app_handle, &task_id, &status_version, "Installation Complete".to_string(), total, total);
+        Ok(())
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let _ = fs::remove_dir_all(&work_dir);
+    result.map(|_| version_id)
+}
@@ -0,0 +1,126 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+const CACHE_DIR: &str = "http_cache";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    // Unix seconds of the last time the server confirmed this copy
+    fetched_at: u64,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+fn cache_paths(app: &AppHandle, url: &str) -> Result<(PathBuf, PathBuf), String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join(CACHE_DIR);
+    let key = format!("{:x}", Sha1::digest(url.as_bytes()));
+    Ok((dir.join(format!("{}.json", key)), dir.join(format!("{}.body", key))))
+}
+
+fn read_cached(app: &AppHandle, url: &str) -> Option<(CacheEntry, String)> {
+    let (meta_path, body_path) = cache_paths(app, url).ok()?;
+    let entry: CacheEntry = serde_json::from_str(&fs::read_to_string(meta_path).ok()?).ok()?;
+    let body = fs::read_to_string(body_path).ok()?;
+    Some((entry, body))
+}
+
+fn write_cached(app: &AppHandle, entry: &CacheEntry, body: Option<&str>) -> Result<(), String> {
+    let (meta_path, body_path) = cache_paths(app, &entry.url)?;
+    if let Some(parent) = meta_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    if let Some(body) = body {
+        fs::write(body_path, body).map_err(|e| e.to_string())?;
+    }
+    fs::write(meta_path, serde_json::to_string(entry).map_err(|e| e.to_string())?).map_err(|e| e.to_string())
+}
+
+pub fn is_offline() -> bool {
+    crate::config::load_config().offline_mode
+}
+
+// Fetches launcher metadata through an on-disk cache: fresh copies are served as-is, stale ones are
+// revalidated with ETag/Last-Modified, and any copy is better than nothing when the network fails
+pub async fn get_text(app: &AppHandle, client: &reqwest::Client, url: &str) -> Result<String, String> {
+    let config = crate::config::load_config();
+    let cached = read_cached(app, url);
+
+    if config.offline_mode {
+        return cached
+            .map(|(_, body)| body)
+            .ok_or_else(|| format!("Offline mode is on and {} has not been cached yet", url));
+    }
+    if let Some((entry, body)) = &cached {
+        if now_secs().saturating_sub(entry.fetched_at) < config.metadata_cache_ttl_secs {
+            return Ok(body.clone());
+        }
+    }
+
+    let mut req = client.get(url);
+    if let Some((entry, _)) = &cached {
+        if let Some(etag) = &entry.etag {
+            req = req.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &entry.last_modified {
+            req = req.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let resp = match req.send().await {
+        Ok(resp) if resp.status() == reqwest::StatusCode::NOT_MODIFIED => {
+            let (mut entry, body) = cached.ok_or_else(|| format!("Unexpected 304 from {}", url))?;
+            entry.fetched_at = now_secs();
+            let _ = write_cached(app, &entry, None);
+            return Ok(body);
+        }
+        Ok(resp) if resp.status().is_success() => resp,
+        Ok(resp) => {
+            let error = format!("HTTP {} from {}", resp.status(), url);
+            return cached.map(|(_, body)| body).ok_or(error);
+        }
+        Err(e) => return cached.map(|(_, body)| body).ok_or_else(|| e.to_string()),
+    };
+
+    let header = |name: reqwest::header::HeaderName| resp.headers().get(name).and_then(|v| v.to_str().ok()).map(|v| v.to_string());
+    let entry = CacheEntry {
+        url: url.to_string(),
+        etag: header(reqwest::header::ETAG),
+        last_modified: header(reqwest::header::LAST_MODIFIED),
+        fetched_at: now_secs(),
+    };
+    let body = match resp.text().await {
+        Ok(body) => body,
+        Err(e) => return cached.map(|(_, body)| body).ok_or_else(|| e.to_string()),
+    };
+    let _ = write_cached(app, &entry, Some(&body));
+    Ok(body)
+}
+
+pub async fn get_json<T: DeserializeOwned>(app: &AppHandle, client: &reqwest::Client, url: &str) -> Result<T, String> {
+    let body = get_text(app, client, url).await?;
+    serde_json::from_str(&body).map_err(|e| format!("Invalid JSON from {}: {}", url, e))
+}
+
+#[tauri::command]
+pub fn set_offline_mode(enabled: bool) -> Result<(), String> {
+    let mut config = crate::config::load_config();
+    config.offline_mode = enabled;
+    crate::config::save_config(&config)
+}
+
+#[tauri::command]
+pub fn set_metadata_cache_ttl(seconds: u64) -> Result<(), String> {
+    let mut config = crate::config::load_config();
+    config.metadata_cache_ttl_secs = seconds;
+    crate::config::save_config(&config)
+}
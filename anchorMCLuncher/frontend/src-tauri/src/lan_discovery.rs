@@ -0,0 +1,147 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, State};
+use tokio::net::UdpSocket;
+
+const LAN_GROUP: Ipv4Addr = Ipv4Addr::new(224, 0, 2, 60);
+const LAN_PORT: u16 = 4445;
+// The game announces every 1.5s; a few missed packets means the world was closed
+const LAN_GAME_TTL: Duration = Duration::from_secs(8);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LanGame {
+    pub motd: String,
+    pub host: String,
+    pub port: u16,
+    // Unix millis of the last announcement
+    pub last_seen: u64,
+}
+
+type LanGames = Arc<Mutex<HashMap<String, (Instant, LanGame)>>>;
+
+pub struct LanDiscoveryState {
+    games: LanGames,
+    listener: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+}
+
+impl LanDiscoveryState {
+    pub fn new() -> Self {
+        Self {
+            games: Arc::new(Mutex::new(HashMap::new())),
+            listener: Mutex::new(None),
+        }
+    }
+}
+
+// Announcements look like "[MOTD]<motd>[/MOTD][AD]<port>[/AD]"; some proxies put host:port in AD
+fn parse_announcement(message: &str, sender: &SocketAddr) -> Option<LanGame> {
+    let motd = between(message, "[MOTD]", "[/MOTD]")?;
+    let ad = between(message, "[AD]", "[/AD]")?;
+    let (host, port) = match ad.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() => (host.to_string(), port.trim().parse().ok()?),
+        _ => (sender.ip().to_string(), ad.trim().parse().ok()?),
+    };
+    let last_seen = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or_default();
+    Some(LanGame {
+        motd: motd.to_string(),
+        host,
+        port,
+        last_seen,
+    })
+}
+
+fn between<'a>(s: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let from = s.find(start)? + start.len();
+    let to = from + s[from..].find(end)?;
+    Some(&s[from..to])
+}
+
+// Other game clients on this machine listen on the same port, so the address must be shared
+fn bind_multicast() -> Result<std::net::UdpSocket, String> {
+    let socket = socket2::Socket::new(socket2::Domain::IPV4, socket2::Type::DGRAM, Some(socket2::Protocol::UDP))
+        .map_err(|e| e.to_string())?;
+    socket.set_reuse_address(true).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true).map_err(|e| e.to_string())?;
+    socket
+        .bind(&SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, LAN_PORT).into())
+        .map_err(|e| format!("Failed to bind LAN discovery port {}: {}", LAN_PORT, e))?;
+    socket.join_multicast_v4(&LAN_GROUP, &Ipv4Addr::UNSPECIFIED).map_err(|e| e.to_string())?;
+    socket.set_nonblocking(true).map_err(|e| e.to_string())?;
+    Ok(socket.into())
+}
+
+fn snapshot(games: &LanGames) -> Vec<LanGame> {
+    let mut list: Vec<LanGame> = games
+        .lock()
+        .map(|games| games.values().map(|(_, game)| game.clone()).collect())
+        .unwrap_or_default();
+    list.sort_by(|a, b| a.motd.cmp(&b.motd).then(a.host.cmp(&b.host)));
+    list
+}
+
+async fn listen(app: AppHandle, socket: UdpSocket, games: LanGames) {
+    let mut buf = [0u8; 1024];
+    loop {
+        let mut changed = false;
+        // Wake up periodically so worlds that stopped announcing expire
+        if let Ok(Ok((len, sender))) = tokio::time::timeout(Duration::from_secs(1), socket.recv_from(&mut buf)).await {
+            let message = String::from_utf8_lossy(&buf[..len]);
+            if let Some(game) = parse_announcement(&message, &sender) {
+                if let Ok(mut games) = games.lock() {
+                    let key = format!("{}:{}", game.host, game.port);
+                    changed = games.get(&key).is_none_or(|(_, existing)| existing.motd != game.motd);
+                    games.insert(key, (Instant::now(), game));
+                }
+            }
+        }
+        if let Ok(mut games) = games.lock() {
+            let before = games.len();
+            games.retain(|_, (seen, _)| seen.elapsed() < LAN_GAME_TTL);
+            changed |= games.len() != before;
+        }
+        if changed {
+            let _ = app.emit("lan-games-updated", snapshot(&games));
+        }
+    }
+}
+
+#[tauri::command]
+pub fn start_lan_discovery(app: AppHandle, state: State<'_, LanDiscoveryState>) -> Result<(), String> {
+    let mut listener = state.listener.lock().map_err(|e| e.to_string())?;
+    if listener.is_some() {
+        return Ok(());
+    }
+    let socket = bind_multicast()?;
+    let games = state.games.clone();
+    *listener = Some(tauri::async_runtime::spawn(async move {
+        // from_std needs a runtime, so register the socket from inside the task
+        match UdpSocket::from_std(socket) {
+            Ok(socket) => listen(app, socket, games).await,
+            Err(e) => {
+                let _ = app.emit("lan-discovery-error", e.to_string());
+            }
+        }
+    }));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_lan_discovery(app: AppHandle, state: State<'_, LanDiscoveryState>) -> Result<(), String> {
+    if let Some(handle) = state.listener.lock().map_err(|e| e.to_string())?.take() {
+        handle.abort();
+    }
+    if let Ok(mut games) = state.games.lock() {
+        games.clear();
+    }
+    let _ = app.emit("lan-games-updated", Vec::<LanGame>::new());
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_lan_games(state: State<'_, LanDiscoveryState>) -> Vec<LanGame> {
+    snapshot(&state.games)
+}
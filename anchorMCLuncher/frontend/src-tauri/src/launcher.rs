@@ -1,18 +1,73 @@
 use tauri::{AppHandle, Manager, Emitter};
 use std::process::{Command, Stdio};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use std::io::{Read, BufRead, BufReader, Write};
 use std::thread;
 use walkdir::WalkDir;
 use serde_json::json;
 
+use crate::download_manager::TaskFile;
+
 #[derive(Debug, serde::Deserialize)]
 pub struct MinecraftAccount {
     pub username: String,
     pub uuid: String,
     pub access_token: String,
     pub user_type: String,
+    pub xuid: Option<String>,
+    pub client_id: Option<String>,
+    pub user_properties: Option<serde_json::Value>,
+    // Only meaningful for Microsoft accounts; Some(false) launches the game in demo mode
+    pub owns_game: Option<bool>,
+}
+
+impl MinecraftAccount {
+    pub(crate) fn offline() -> Self {
+        Self {
+            username: "Player".to_string(),
+            uuid: "00000000-0000-0000-0000-000000000000".to_string(),
+            access_token: "00000000-0000-0000-0000-000000000000".to_string(),
+            user_type: "mojang".to_string(),
+            xuid: None,
+            client_id: None,
+            user_properties: None,
+            owns_game: None,
+        }
+    }
+
+    fn is_microsoft(&self) -> bool {
+        matches!(self.user_type.as_str(), "msa" | "microsoft")
+    }
+
+    // Value for ${user_type}: "msa" for Microsoft, "legacy" for offline, "mojang" for Yggdrasil servers
+    fn launch_user_type(&self) -> &str {
+        match self.user_type.as_str() {
+            "msa" | "microsoft" => "msa",
+            "legacy" | "offline" => "legacy",
+            _ => "mojang",
+        }
+    }
+
+    // Xbox user id and client id only exist for Microsoft accounts
+    fn launch_xuid(&self) -> String {
+        self.xuid.clone().filter(|_| self.is_microsoft()).unwrap_or_else(|| "0".to_string())
+    }
+
+    fn launch_client_id(&self) -> String {
+        self.client_id.clone().filter(|_| self.is_microsoft()).unwrap_or_else(|| "0".to_string())
+    }
+
+    fn launch_user_properties(&self) -> String {
+        match &self.user_properties {
+            Some(props) if props.is_object() => props.to_string(),
+            _ => "{}".to_string(),
+        }
+    }
+
+    fn is_demo(&self) -> bool {
+        self.is_microsoft() && self.owns_game == Some(false)
+    }
 }
 
 #[derive(serde::Serialize)]
@@ -88,38 +143,18 @@ pub fn get_version_details(app: AppHandle, version_id: String, game_path: Option
     let file = std::fs::File::open(&json_path).map_err(|e| e.to_string())?;
     let json: serde_json::Value = serde_json::from_reader(file).map_err(|e| e.to_string())?;
     
-    let version_type = json["type"].as_str().unwrap_or("release").to_string();
-    let mut main_class = json["mainClass"].as_str().unwrap_or("").to_string();
-
-    if main_class.is_empty() {
-        if let Some(parent_id) = json.get("inheritsFrom").and_then(|v| v.as_str()) {
-            let parent_dir = mc_dir.join("versions").join(parent_id);
-            let parent_json_path = parent_dir.join(format!("{}.json", parent_id));
-            if parent_json_path.exists() {
-                if let Ok(file) = std::fs::File::open(&parent_json_path) {
-                    if let Ok(p_json) = serde_json::from_reader::<_, serde_json::Value>(file) {
-                        if let Some(p_main) = p_json.get("mainClass").and_then(|v| v.as_str()) {
-                            main_class = p_main.to_string();
-                        } else if let Some(gp_id) = p_json.get("inheritsFrom").and_then(|v| v.as_str()) {
-                            let gp_dir = mc_dir.join("versions").join(gp_id);
-                            let gp_json_path = gp_dir.join(format!("{}.json", gp_id));
-                            if gp_json_path.exists() {
-                                if let Ok(gp_file) = std::fs::File::open(&gp_json_path) {
-                                    if let Ok(gp_json) = serde_json::from_reader::<_, serde_json::Value>(gp_file) {
-                                        if let Some(gp_main) = gp_json.get("mainClass").and_then(|v| v.as_str()) {
-                                            main_class = gp_main.to_string();
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    let is_modded = version_type == "modpack" || (!main_class.is_empty() && main_class != "net.minecraft.client.main.Main");
+    // Loader profiles copy or make up their own "type", so snapshots and old versions are told apart by the base game
+    let chain = version_chain(&mc_dir, &json);
+    let base = chain.last().unwrap_or(&json);
+    let version_type = base["type"].as_str().unwrap_or("release").to_string();
+    // Anything replacing the game's own entry point is a loader; pre-1.6 versions start through launchwrapper by themselves
+    let main_class = chain.iter().find_map(|j| j["mainClass"].as_str()).unwrap_or_default();
+    let is_modded = json["type"].as_str() == Some("modpack")
+        || if chain.len() > 1 {
+            main_class != base["mainClass"].as_str().unwrap_or_default()
+        } else {
+            !is_vanilla_entry(&json)
+        };
 
     Ok(VersionDetails {
         is_modded,
@@ -154,13 +189,29 @@ pub fn get_version_runtime_info(app: AppHandle, version_id: String, game_path: O
     let mut loader_version: Option<String> = None;
     let mut mc_version: Option<String> = None;
 
+    // "<loader>-<game>" tails: loader versions like "0.19.0-beta.18" have dashes too, so split off the known game version
+    let inherits = json.get("inheritsFrom").and_then(|v| v.as_str());
+    let split_loader_tail = |tail: &str| -> Option<(String, String)> {
+        match inherits.and_then(|mc| Some((tail.strip_suffix(mc)?.strip_suffix('-')?, mc))) {
+            Some((lv, mv)) => Some((lv.to_string(), mv.to_string())),
+            None => tail.split_once('-').map(|(lv, mv)| (lv.to_string(), mv.to_string())),
+        }
+    };
+
     for id in candidate_ids.iter() {
         if id.starts_with("fabric-loader-") {
             loader_type = Some("fabric".to_string());
-            let tail = id.trim_start_matches("fabric-loader-");
-            if let Some((lv, mv)) = tail.split_once('-') {
-                loader_version = Some(lv.to_string());
-                mc_version = Some(mv.to_string());
+            if let Some((lv, mv)) = split_loader_tail(id.trim_start_matches("fabric-loader-")) {
+                loader_version = Some(lv);
+                mc_version = Some(mv);
+            }
+            break;
+        }
+        if id.starts_with("quilt-loader-") {
+            loader_type = Some("quilt".to_string());
+            if let Some((lv, mv)) = split_loader_tail(id.trim_start_matches("quilt-loader-")) {
+                loader_version = Some(lv);
+                mc_version = Some(mv);
             }
             break;
         }
@@ -182,10 +233,23 @@ pub fn get_version_runtime_info(app: AppHandle, version_id: String, game_path: O
             }
             break;
         }
-        if id.chars().next().map(|c| c.is_ascii_digit()).unwrap_or(false) {
+        // Installer ids: "1.20.1-forge-47.2.0", "1.12.2-forge1.12.2-14.23.5.2847", "1.7.10-Forge10.13.4.1614-1.7.10"
+        if let Some(pos) = id.to_ascii_lowercase().find("-forge") {
+            let mv = &id[..pos];
+            let tail = id[pos + "-forge".len()..].trim_start_matches('-');
+            let lv = tail
+                .strip_prefix(&format!("{}-", mv))
+                .or_else(|| tail.strip_suffix(&format!("-{}", mv)))
+                .unwrap_or(tail);
+            loader_type = Some("forge".to_string());
+            mc_version = Some(mv.to_string());
+            loader_version = Some(lv.to_string());
+            break;
+        }
+        // A plain game version only means vanilla if the version's own id doesn't name a loader
+        if mc_version.is_none() && id.chars().next().map(|c| c.is_ascii_digit()).unwrap_or(false) {
             mc_version = Some(id.to_string());
             loader_type = Some("vanilla".to_string());
-            break;
         }
     }
 
@@ -313,6 +377,117 @@ fn should_use_library(lib: &serde_json::Value) -> bool {
     true
 }
 
+// The version json followed by every version it inherits from
+// For jsons with nothing to compare against: the game's own main classes, with only Mojang's tweakers for launchwrapper
+fn is_vanilla_entry(json: &serde_json::Value) -> bool {
+    let main_class = json["mainClass"].as_str().unwrap_or_default();
+    let mut args = json["minecraftArguments"].as_str().unwrap_or_default().split_whitespace();
+    let vanilla_tweakers = std::iter::from_fn(|| {
+        args.find(|arg| *arg == "--tweakClass")?;
+        args.next()
+    })
+    .all(|tweaker| tweaker.starts_with("net.minecraft.launchwrapper."));
+    matches!(main_class, "" | "net.minecraft.client.main.Main" | "net.minecraft.client.Minecraft" | "net.minecraft.launchwrapper.Launch")
+        && vanilla_tweakers
+}
+
+fn version_chain(mc_dir: &Path, json: &serde_json::Value) -> Vec<serde_json::Value> {
+    let mut chain = vec![json.clone()];
+    while let Some(parent_id) = chain.last().and_then(|j| j["inheritsFrom"].as_str()) {
+        // Guard against inheritance loops in hand-edited jsons
+        if chain.len() > 8 {
+            break;
+        }
+        let parent_path = mc_dir.join("versions").join(parent_id).join(format!("{}.json", parent_id));
+        let Some(parent) = std::fs::read_to_string(parent_path).ok().and_then(|c| serde_json::from_str(&c).ok()) else {
+            break;
+        };
+        chain.push(parent);
+    }
+    chain
+}
+
+// Weekly snapshot ids like "24w14a" as (year, week)
+fn snapshot_week(id: &str) -> Option<(u32, u32)> {
+    let (year, rest) = id.split_once('w')?;
+    if year.len() != 2 || rest.len() < 3 || !rest[2..].chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some((year.parse().ok()?, rest[..2].parse().ok()?))
+}
+
+fn required_java_version(chain: &[serde_json::Value]) -> u32 {
+    // Mojang's jsons state it; older installs and custom jsons may not
+    if let Some(major) = chain.iter().find_map(|json| json["javaVersion"]["majorVersion"].as_u64()) {
+        return major as u32;
+    }
+    let id = chain.last().and_then(|json| json["id"].as_str()).unwrap_or_default();
+    if let Some(week) = snapshot_week(id) {
+        return if week >= (24, 14) {
+            21
+        } else if week >= (21, 44) {
+            17
+        } else if week >= (21, 19) {
+            16
+        } else {
+            8
+        };
+    }
+    if id.starts_with("1.21") || id.starts_with("1.20.5") || id.starts_with("1.20.6") {
+        21
+    } else if id.starts_with("1.18") || id.starts_with("1.19") || id.starts_with("1.20") {
+        17
+    } else if id.starts_with("1.17") {
+        16
+    } else {
+        8
+    }
+}
+
+// Games before 1.7.3 look assets up by name: "legacy" indexes want a virtual copy of the objects,
+// "pre-1.6" ones a resources folder in the game directory. Returns the folder for ${game_assets}.
+fn reconstruct_legacy_assets(mc_dir: &Path, asset_index_id: &str, game_dir: &Path) -> Result<PathBuf, String> {
+    let assets_root = mc_dir.join("assets");
+    let index_path = assets_root.join("indexes").join(format!("{}.json", asset_index_id));
+    let Some(index) = std::fs::read_to_string(index_path).ok().and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok()) else {
+        return Ok(assets_root);
+    };
+    let target = if index["map_to_resources"].as_bool() == Some(true) {
+        game_dir.join("resources")
+    } else if index["virtual"].as_bool() == Some(true) {
+        assets_root.join("virtual").join(asset_index_id)
+    } else {
+        return Ok(assets_root);
+    };
+
+    if let Some(objects) = index["objects"].as_object() {
+        for (name, object) in objects {
+            let Some(hash) = object["hash"].as_str().filter(|h| h.len() > 2) else {
+                continue;
+            };
+            let source = assets_root.join("objects").join(&hash[..2]).join(hash);
+            let dest = target.join(name);
+            if dest.exists() || !source.exists() {
+                continue;
+            }
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+            }
+            std::fs::copy(&source, &dest).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(target)
+}
+
+// Versions that want Java 8 start through launchwrapper or old Forge, which break on the module system of 9+
+fn java_fits(installed: u32, required: u32) -> bool {
+    if required == 8 {
+        installed == 8
+    } else {
+        installed >= required
+    }
+}
+
 fn get_java_version(java_path: &str) -> Option<u32> {
     let output = Command::new(java_path).arg("-version").output().ok()?;
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -343,7 +518,88 @@ fn get_java_version(java_path: &str) -> Option<u32> {
     None
 }
 
-async fn ensure_java(app: &AppHandle, required_version: u32) -> Result<String, String> {
+// Picks a Java that can run `required_java`: the user's, then a managed runtime, then the system one, downloading as a last resort
+pub(crate) async fn select_java(app: &AppHandle, java_path: Option<&str>, required_java: u32) -> Result<String, String> {
+    let mut final_java_path = "java".to_string();
+    let mut java_ok = false;
+
+    // 1. Check User Provided Path
+    if let Some(path) = java_path {
+        if !path.is_empty() {
+            if let Some(ver) = get_java_version(path) {
+                if java_fits(ver, required_java) {
+                    final_java_path = path.to_string();
+                    java_ok = true;
+                } else {
+                    let _ = app.emit("launch-status", format!("Warning: User Java version {} can't run this version, which needs Java {}.", ver, required_java));
+                }
+            }
+        }
+    }
+
+    // 2. Check Managed Runtime (Preferred over system java for exact match)
+    if !java_ok {
+        let runtimes_dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join("runtimes");
+        let java_dir_name = format!("java-{}", required_java);
+        let java_bin = runtimes_dir.join(&java_dir_name).join("bin").join("java.exe");
+        
+        if java_bin.exists() {
+            final_java_path = java_bin.to_string_lossy().to_string();
+            java_ok = true;
+        }
+    }
+
+    // 3. Check System Java (if user path invalid or not provided)
+    if !java_ok {
+        if let Some(ver) = get_java_version("java") {
+            if java_fits(ver, required_java) {
+                final_java_path = "java".to_string();
+                java_ok = true;
+            }
+        }
+    }
+
+    // 4. Auto-download if needed (Only for 8, 17 and 21)
+    if !java_ok && crate::http_cache::is_offline() {
+        return Err(format!("Java {} not found and offline mode is on. Please install it or pick a Java path.", required_java));
+    }
+    // Only 8, 17 and 21 are downloadable; the next one up runs versions that ask for something in between
+    let download_java = [8, 17, 21].into_iter().find(|v| *v >= required_java);
+    if let Some(download_java) = download_java.filter(|_| !java_ok) {
+        let _ = app.emit("launch-status", format!("Java {} not found. Attempting to download...", download_java));
+        match ensure_java(app, download_java).await {
+            Ok(path) => {
+                final_java_path = path;
+                java_ok = true;
+            },
+            Err(e) => {
+                return Err(format!("Failed to download Java: {}", e));
+            }
+        }
+    }
+
+    if !java_ok {
+        // Final check
+        if Command::new(&final_java_path).arg("-version").output().is_err() {
+             return Err(format!("Java environment not found. Please install Java {} (JRE/JDK).", required_java));
+        }
+        // If it exists but version is wrong, we might still try to launch but warn?
+        // For now, let it proceed, maybe it works.
+    }
+
+
+    Ok(final_java_path)
+}
+
+// Java for running tools against an installed version, such as installer processors
+pub(crate) async fn java_for_version(app: &AppHandle, mc_dir: &Path, version_id: &str, java_path: Option<&str>) -> Result<String, String> {
+    let json_path = mc_dir.join("versions").join(version_id).join(format!("{}.json", version_id));
+    let content = std::fs::read_to_string(&json_path).map_err(|e| format!("Version {} not installed: {}", version_id, e))?;
+    let json: serde_json::Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    select_java(app, java_path, required_java_version(&version_chain(mc_dir, &json))).await
+}
+
+pub(crate) async fn ensure_java(app: &AppHandle, required_version: u32) -> Result<String, String> {
     let runtimes_dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join("runtimes");
     let java_dir_name = format!("java-{}", required_version);
     let java_home = runtimes_dir.join(&java_dir_name);
@@ -413,6 +669,7 @@ pub async fn launch_game(
     java_path: Option<String>,
     account: Option<MinecraftAccount>,
     auth_server: Option<String>,
+    session: Option<crate::auth::AuthSession>,
     jvm_args: Option<Vec<String>>,
     min_memory: Option<u32>,
     max_memory: Option<u32>,
@@ -429,81 +686,6 @@ pub async fn launch_game(
     let config = crate::config::load_config();
     let isolated = crate::config::should_isolate(&config.isolation_mode, details.is_modded, &details.version_type);
     
-    // Determine required Java version
-    let required_java = if version_id.starts_with("1.21") || version_id.starts_with("1.20.5") || version_id.starts_with("1.20.6") {
-        21
-    } else if version_id.starts_with("1.18") || version_id.starts_with("1.19") || version_id.starts_with("1.20") {
-        17
-    } else if version_id.starts_with("1.17") {
-        16
-    } else {
-        8
-    };
-
-    let mut final_java_path = "java".to_string();
-    let mut java_ok = false;
-
-    // 1. Check User Provided Path
-    if let Some(path) = &java_path {
-        if !path.is_empty() {
-            if let Some(ver) = get_java_version(path) {
-                if ver >= required_java {
-                    final_java_path = path.clone();
-                    java_ok = true;
-                } else {
-                    let _ = app.emit("launch-status", format!("Warning: User Java version {} is lower than required {}.", ver, required_java));
-                }
-            }
-        }
-    }
-
-    // 2. Check Managed Runtime (Preferred over system java for exact match)
-    if !java_ok {
-        let runtimes_dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join("runtimes");
-        let java_dir_name = format!("java-{}", required_java);
-        let java_bin = runtimes_dir.join(&java_dir_name).join("bin").join("java.exe");
-        
-        if java_bin.exists() {
-            final_java_path = java_bin.to_string_lossy().to_string();
-            java_ok = true;
-        }
-    }
-
-    // 3. Check System Java (if user path invalid or not provided)
-    if !java_ok {
-        if let Some(ver) = get_java_version("java") {
-            if ver >= required_java {
-                final_java_path = "java".to_string();
-                java_ok = true;
-            }
-        }
-    }
-
-    // 4. Auto-download if needed (Only for 8, 17 and 21)
-    if !java_ok && (required_java == 21 || required_java == 17 || required_java == 8) {
-        let _ = app.emit("launch-status", format!("Java {} not found. Attempting to download...", required_java));
-        match ensure_java(&app, required_java).await {
-            Ok(path) => {
-                final_java_path = path;
-                java_ok = true;
-            },
-            Err(e) => {
-                return Err(format!("Failed to download Java: {}", e));
-            }
-        }
-    }
-
-    if !java_ok {
-        // Final check
-        if Command::new(&final_java_path).arg("-version").output().is_err() {
-             return Err(format!("Java environment not found. Please install Java {} (JRE/JDK).", required_java));
-        }
-        // If it exists but version is wrong, we might still try to launch but warn?
-        // For now, let it proceed, maybe it works.
-    }
-
-    let java_bin = final_java_path;
-
     let mc_dir = crate::version_path::get_game_root(&app, game_path.clone())?;
     let version_dir = crate::version_path::get_version_dir(&app, &version_id, game_path.clone())?;
     let json_path = version_dir.join(format!("{}.json", version_id));
@@ -512,6 +694,18 @@ pub async fn launch_game(
         return Err(format!("Version {} not installed (missing json)", version_id));
     }
 
+    // Read JSON
+    let file = std::fs::File::open(&json_path).map_err(|e| e.to_string())?;
+    let json: serde_json::Value = serde_json::from_reader(file).map_err(|e| e.to_string())?;
+    let chain = version_chain(&mc_dir, &json);
+    // The manifest's type of the base game; loader and modpack jsons don't carry a meaningful one
+    let version_type = chain.last().and_then(|j| j["type"].as_str()).unwrap_or("release").to_string();
+
+    // Determine required Java version
+    let required_java = required_java_version(&chain);
+
+    let java_bin = select_java(&app, java_path.as_deref(), required_java).await?;
+
     let _ = app.emit("launch-status", "Preparing natives...");
     // Natives Directory
     let natives_dir = version_dir.join("natives");
@@ -520,10 +714,6 @@ pub async fn launch_game(
         let _ = std::fs::remove_dir_all(&natives_dir);
     }
     std::fs::create_dir_all(&natives_dir).map_err(|e| e.to_string())?;
-
-    // Read JSON
-    let file = std::fs::File::open(&json_path).map_err(|e| e.to_string())?;
-    let json: serde_json::Value = serde_json::from_reader(file).map_err(|e| e.to_string())?;
     
     // Inheritance Handling
     let mut jar_path = version_dir.join(format!("{}.jar", version_id));
@@ -668,7 +858,7 @@ pub async fn launch_game(
             if let Some(natives) = lib["natives"].as_object() {
                 if let Some(classifier_val) = natives.get(os_key) {
                     if let Some(classifier) = classifier_val.as_str() {
-                         let arch = if cfg!(target_arch = "x86") { "x86" } else { "64" };
+                         let arch = if cfg!(target_arch = "x86") { "32" } else { "64" };
                          let key = classifier.replace("${arch}", arch);
                          
                          if let Some(downloads) = lib["downloads"].as_object() {
@@ -813,103 +1003,16 @@ pub async fn launch_game(
     // Arguments
     let mut args = Vec::new();
     
-    // Authlib Injector
-    if let Some(server_url) = auth_server {
-        let injector_path = mc_dir.join("authlib-injector.jar");
-        if !injector_path.exists() {
-            let _ = app.emit("launch-status", format!("Downloading authlib-injector to {:?}...", injector_path));
-            
-            // Ensure parent directory exists
-            if let Some(parent) = injector_path.parent() {
-                if !parent.exists() {
-                     let _ = std::fs::create_dir_all(parent);
-                }
-            }
-
-            let mirrors = vec![
-                "https://bmclapi2.bangbang93.com/mirrors/authlib-injector/artifact/latest.json",
-                "https://openbmclapi.bangbang93.com/mirrors/authlib-injector/artifact/latest.json",
-                "https://authlib-injector.yushi.moe/artifact/latest.json",
-            ];
-            
-            let client = reqwest::Client::new();
-            let mut downloaded = false;
-            let mut last_error = String::new();
-
-            for mirror_url in mirrors {
-                let _ = app.emit("launch-status", format!("Checking mirror metadata: {}...", mirror_url));
-                
-                // 1. Fetch Metadata
-                match client.get(mirror_url).send().await {
-                    Ok(resp) => {
-                        if resp.status().is_success() {
-                            match resp.json::<serde_json::Value>().await {
-                                Ok(json) => {
-                                    if let Some(download_url) = json.get("download_url").and_then(|v| v.as_str()) {
-                                        let _ = app.emit("launch-status", format!("Found download URL: {}", download_url));
-                                        
-                                        // 2. Download File
-                                        match client.get(download_url).send().await {
-                                            Ok(file_resp) => {
-                                                if file_resp.status().is_success() {
-                                                    match file_resp.bytes().await {
-                                                        Ok(bytes) => {
-                                                            if let Err(e) = tokio::fs::write(&injector_path, bytes).await {
-                                                                last_error = format!("Write error: {}", e);
-                                                                let _ = app.emit("launch-status", format!("Failed to write file: {}", e));
-                                                            } else {
-                                                                downloaded = true;
-                                                                let _ = app.emit("launch-status", "Download successful.");
-                                                                break;
-                                                            }
-                                                        }
-                                                        Err(e) => {
-                                                            last_error = format!("Bytes error: {}", e);
-                                                            let _ = app.emit("launch-status", format!("Failed to read bytes: {}", e));
-                                                        }
-                                                    }
-                                                } else {
-                                                    last_error = format!("File download HTTP error: {}", file_resp.status());
-                                                    let _ = app.emit("launch-status", format!("File download HTTP error: {}", file_resp.status()));
-                                                }
-                                            }
-                                            Err(e) => {
-                                                last_error = format!("File request error: {}", e);
-                                                let _ = app.emit("launch-status", format!("File request error: {}", e));
-                                            }
-                                        }
-                                    } else {
-                                        last_error = "Invalid JSON: missing download_url".to_string();
-                                        let _ = app.emit("launch-status", "Invalid JSON: missing download_url");
-                                    }
-                                }
-                                Err(e) => {
-                                    last_error = format!("JSON parse error: {}", e);
-                                    let _ = app.emit("launch-status", format!("JSON parse error: {}", e));
-                                }
-                            }
-                        } else {
-                            last_error = format!("Metadata HTTP error: {}", resp.status());
-                            let _ = app.emit("launch-status", format!("Metadata HTTP error: {}", resp.status()));
-                        }
-                    }
-                    Err(e) => {
-                        last_error = format!("Metadata request error: {}", e);
-                        let _ = app.emit("launch-status", format!("Metadata request error: {}", e));
-                    }
-                }
-                
-                if downloaded {
-                    break;
-                }
-            }
-
-            if !downloaded {
-                return Err(format!("Failed to download authlib-injector from any mirror. Last error: {}", last_error));
-            }
-        }
-        args.push(format!("-javaagent:{}={}", injector_path.to_string_lossy(), server_url));
-    }
+    // Account (authlib-injector and other provider specific JVM args)
+    let session = session.unwrap_or_else(|| crate::auth::AuthSession::from_legacy(account, auth_server));
+    let provider = app.state::<crate::auth::AuthRegistry>().get(&session.provider)?;
+    let launch_ctx = crate::auth::LaunchContext {
+        app: app.clone(),
+        mc_dir: mc_dir.clone(),
+        version_dir: version_dir.clone(),
+    };
+    let launch_auth = provider.launch_arguments(&launch_ctx, &session).await?;
+    args.extend(launch_auth.jvm_args);
 
     // Memory
     if let Some(min) = min_memory {
@@ -1025,7 +1128,7 @@ pub async fn launch_game(
                 .replace("${classpath}", classpath_value)
                 .replace("${classpath_separator}", cp_separator)
                 .replace("${version_name}", &version_id)
-                .replace("${version_type}", "release");
+                .replace("${version_type}", &version_type);
             
             // Fix for FabricMcEmu argument having spaces
             // Some versions of Fabric/Loader might have a malformed argument in their json or it's parsed incorrectly
@@ -1052,9 +1155,12 @@ pub async fn launch_game(
     // Game Arguments Parsing
     let mut game_args = Vec::new();
     
+    let account = launch_auth.account;
+    let is_demo = account.is_demo();
+
     let has_custom_resolution = width.is_some() || height.is_some();
     let feature_flags: std::collections::HashMap<&str, bool> = [
-        ("is_demo_user", false),
+        ("is_demo_user", is_demo),
         ("has_custom_resolution", has_custom_resolution),
         ("quickPlayPath", false),
         ("quickPlaySingleplayer", false),
@@ -1129,7 +1235,8 @@ pub async fn launch_game(
                  }
             }
         } else if let Some(mc_args) = json_obj.get("minecraftArguments").and_then(|v| v.as_str()) {
-            // Old format
+            // Old format: the full argument string, replacing whatever the parent declared (e.g. legacy Forge adds --tweakClass)
+            args_vec.clear();
             for arg in mc_args.split_whitespace() {
                 args_vec.push(arg.to_string());
             }
@@ -1193,6 +1300,11 @@ pub async fn launch_game(
         game_args.push("${user_type}".to_string());
     }
 
+    // Legacy argument formats have no is_demo_user rule, so add the flag ourselves
+    if is_demo && !game_args.iter().any(|arg| arg == "--demo") {
+        game_args.push("--demo".to_string());
+    }
+
     // Replacements
     let mut asset_index_id = json.get("assetIndex").and_then(|ai| ai.get("id")).and_then(|i| i.as_str()).unwrap_or("").to_string();
     if asset_index_id.is_empty() {
@@ -1206,14 +1318,11 @@ pub async fn launch_game(
         }
     }
     
-    let (username, uuid, access_token, user_type) = if let Some(acc) = account {
-        (acc.username, acc.uuid, acc.access_token, acc.user_type)
-    } else {
-        ("Player".to_string(), "00000000-0000-0000-0000-000000000000".to_string(), "00000000-0000-0000-0000-000000000000".to_string(), "mojang".to_string())
-    };
-
-    let client_id = "0".to_string();
-    let auth_xuid = "0".to_string();
+    let user_type = account.launch_user_type().to_string();
+    let user_properties = account.launch_user_properties();
+    let client_id = account.launch_client_id();
+    let auth_xuid = account.launch_xuid();
+    let MinecraftAccount { username, uuid, access_token, .. } = account;
 
     // Determine Game Directory (Isolation)
     let final_game_dir = crate::version_path::get_game_working_dir(&app, &version_id, game_path.clone(), isolated)?;
@@ -1225,6 +1334,8 @@ pub async fn launch_game(
         println!("Warning: Failed to apply override folders: {}", e);
     }
 
+    let game_assets = reconstruct_legacy_assets(&mc_dir, &asset_index_id, &final_game_dir)?;
+
     let resolution_width = width.map(|w| w.to_string());
     let resolution_height = height.map(|h| h.to_string());
 
@@ -1238,8 +1349,10 @@ pub async fn launch_game(
             .replace("${auth_uuid}", &uuid)
             .replace("${auth_access_token}", &access_token)
             .replace("${user_type}", &user_type)
-            .replace("${version_type}", "release")
-            .replace("${user_properties}", "{}")
+            .replace("${version_type}", &version_type)
+            .replace("${game_assets}", &game_assets.to_string_lossy())
+            .replace("${auth_session}", &format!("token:{}:{}", access_token, uuid))
+            .replace("${user_properties}", &user_properties)
             .replace("${clientid}", &client_id)
             .replace("${auth_xuid}", &auth_xuid);
 
@@ -1267,7 +1380,7 @@ pub async fn launch_game(
             *arg = arg.replace("${version_name}", &version_id);
         }
         if arg.contains("${version_type}") {
-            *arg = arg.replace("${version_type}", "release");
+            *arg = arg.replace("${version_type}", &version_type);
         }
     }
 
@@ -1761,6 +1874,10 @@ pub async fn import_modpack(
             current_file_progress: None,
             current_file_downloaded: None,
             current_file_total: None,
+            speed: None,
+            eta_seconds: None,
+            downloaded_bytes: None,
+            total_bytes: None,
         });
 
         let client = reqwest::Client::new();
@@ -1799,6 +1916,10 @@ pub async fn import_modpack(
             current_file_progress: None,
             current_file_downloaded: None,
             current_file_total: None,
+            speed: None,
+            eta_seconds: None,
+            downloaded_bytes: None,
+            total_bytes: None,
         });
 
         let index_content = {
@@ -1823,7 +1944,7 @@ pub async fn import_modpack(
             if let Some(url) = file.downloads.first() {
                 // file.path is like "mods/fabric-api.jar"
                 let path = target_root.join(&file.path);
-                queue.push((url.clone(), path, None));
+                queue.push(TaskFile::new(url.clone(), path, None));
             }
         }
 
@@ -1841,6 +1962,10 @@ pub async fn import_modpack(
                     current_file_progress: None,
                     current_file_downloaded: None,
                     current_file_total: None,
+                    speed: None,
+                    eta_seconds: None,
+                    downloaded_bytes: None,
+                    total_bytes: None,
                 });
             }
 
@@ -1876,16 +2001,22 @@ pub async fn import_modpack(
         if let Some(mc_ver) = index.dependencies.get("minecraft") {
             mc_version = Some(mc_ver.to_string());
             // Download vanilla
-            if let Ok((vanilla_queue, _)) = crate::downloader::prepare_vanilla_downloads(&client, mc_ver, &mc_dir).await {
+            if let Ok((vanilla_queue, _)) = crate::downloader::prepare_vanilla_downloads(&app, &client, mc_ver, &mc_dir).await {
                 queue.extend(vanilla_queue);
             }
 
             if let Some(fabric_ver) = index.dependencies.get("fabric-loader") {
-                 if let Ok((loader_queue, loader_id)) = crate::downloader::prepare_fabric_downloads(&client, mc_ver, fabric_ver, &mc_dir).await {
+                 if let Ok((loader_queue, loader_id)) = crate::downloader::prepare_fabric_downloads(&app, &client, mc_ver, fabric_ver, &mc_dir).await {
                      queue.extend(loader_queue);
                      parent_version_id = Some(loader_id);
                      loader_kind = Some("fabric".to_string());
                  }
+            } else if let Some(quilt_ver) = index.dependencies.get("quilt-loader") {
+                 if let Ok((loader_queue, loader_id)) = crate::downloader::prepare_quilt_downloads(&app, &client, mc_ver, quilt_ver, &mc_dir).await {
+                     queue.extend(loader_queue);
+                     parent_version_id = Some(loader_id);
+                     loader_kind = Some("quilt".to_string());
+                 }
             } else if let Some(neoforge_ver) = index.dependencies.get("neoforge") {
                 loader_install = Some(("neoforge".to_string(), neoforge_ver.to_string()));
                 loader_kind = Some("neoforge".to_string());
@@ -1900,7 +2031,6 @@ pub async fn import_modpack(
         if let (Some(mc_ver), Some((loader_type, loader_ver))) = (mc_version.as_deref(), loader_install) {
             crate::downloader::install_forge_or_neoforge(
                 &app,
-                &client,
                 mc_ver,
                 &loader_type,
                 &loader_ver,
@@ -1943,6 +2073,8 @@ pub async fn import_modpack(
 
                 if loader_kind.as_deref() == Some("fabric") {
                     json_content["mainClass"] = serde_json::Value::String("net.fabricmc.loader.impl.launch.knot.KnotClient".to_string());
+                } else if loader_kind.as_deref() == Some("quilt") {
+                    json_content["mainClass"] = serde_json::Value::String("org.quiltmc.loader.impl.launch.knot.KnotClient".to_string());
                 }
                 // Note: mainClass might be redundant if inheritsFrom works correctly, but good to have.
                 // Actually, fabric loader json has the mainClass.
@@ -1966,6 +2098,9 @@ pub async fn import_modpack(
              }
         }
 
+    } else if let Some(id) = crate::downloader::find_version_archive(&archive) {
+        // A bare version folder, as Mojang ships experimental snapshots
+        crate::downloader::install_version_archive(&app, &mut archive, &id, &mc_dir, task_id.clone()).await?;
     } else {
         // Legacy Zip Import (Existing Logic)
         let _ = app.emit("download-progress", crate::downloader::DownloadProgress {
@@ -1978,6 +2113,10 @@ pub async fn import_modpack(
             current_file_progress: None,
             current_file_downloaded: None,
             current_file_total: None,
+            speed: None,
+            eta_seconds: None,
+            downloaded_bytes: None,
+            total_bytes: None,
         });
 
         // 1. Scan for version ID
@@ -2029,6 +2168,10 @@ pub async fn import_modpack(
             current_file_progress: None,
             current_file_downloaded: None,
             current_file_total: None,
+            speed: None,
+            eta_seconds: None,
+            downloaded_bytes: None,
+            total_bytes: None,
         });
 
         for i in 0..archive.len() {
@@ -2113,6 +2256,10 @@ pub async fn import_modpack(
                 current_file_progress: None,
                 current_file_downloaded: None,
                 current_file_total: None,
+                speed: None,
+                eta_seconds: None,
+                downloaded_bytes: None,
+                total_bytes: None,
             });
 
             let version_json_path = mc_dir.join("versions").join(&final_version_id).join(format!("{}.json", final_version_id));
@@ -2124,7 +2271,7 @@ pub async fn import_modpack(
 
             // A. If it inherits from vanilla, download vanilla stuff
             if let Some(inherits_from) = json.get("inheritsFrom").and_then(|v| v.as_str()) {
-                 if let Ok((vanilla_queue, _)) = crate::downloader::prepare_vanilla_downloads(&client, inherits_from, &mc_dir).await {
+                 if let Ok((vanilla_queue, _)) = crate::downloader::prepare_vanilla_downloads(&app, &client, inherits_from, &mc_dir).await {
                      queue.extend(vanilla_queue);
                  }
             } else {
@@ -2147,7 +2294,7 @@ pub async fn import_modpack(
                  // Unless the json still contains "assets": "1.20.1".
                  
                  if let Some(assets_version) = json.get("assets").and_then(|v| v.as_str()) {
-                      if let Ok((vanilla_queue, _)) = crate::downloader::prepare_vanilla_downloads(&client, assets_version, &mc_dir).await {
+                      if let Ok((vanilla_queue, _)) = crate::downloader::prepare_vanilla_downloads(&app, &client, assets_version, &mc_dir).await {
                           queue.extend(vanilla_queue);
                       }
                  }
@@ -2159,7 +2306,7 @@ pub async fn import_modpack(
                     if let Some(url) = client_artifact.get("url").and_then(|u| u.as_str()) {
                         let jar_path = mc_dir.join("versions").join(&final_version_id).join(format!("{}.jar", final_version_id));
                         if !jar_path.exists() {
-                            queue.push((url.to_string(), jar_path, None));
+                            queue.push(TaskFile::new(url.to_string(), jar_path, None));
                         }
                     }
                 }
@@ -2173,7 +2320,7 @@ pub async fn import_modpack(
                              if let Some(artifact) = downloads.get("artifact") {
                                  if let (Some(url), Some(path)) = (artifact.get("url").and_then(|u| u.as_str()), artifact.get("path").and_then(|p| p.as_str())) {
                                      let full_path = mc_dir.join("libraries").join(path);
-                                     queue.push((url.to_string(), full_path, None));
+                                     queue.push(TaskFile::new(url.to_string(), full_path, None));
                                  }
                              }
                          } else {
@@ -2188,7 +2335,7 @@ pub async fn import_modpack(
                                      let full_path = mc_dir.join("libraries").join(&path);
                                      let base_url = lib.get("url").and_then(|u| u.as_str()).unwrap_or("https://libraries.minecraft.net/");
                                      let url = format!("{}{}", base_url, path);
-                                     queue.push((url, full_path, None));
+                                     queue.push(TaskFile::new(url, full_path, None));
                                  }
                              }
                          }
@@ -2215,14 +2362,30 @@ pub async fn import_modpack(
         current_file_progress: None,
         current_file_downloaded: None,
         current_file_total: None,
+        speed: None,
+        eta_seconds: None,
+        downloaded_bytes: None,
+        total_bytes: None,
     });
 
     Ok(())
 }
 
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, Default)]
 struct ClientConfig {
     server_id: Option<i32>,
+    authlib_injector_build: Option<u32>,
+}
+
+fn read_client_config(version_dir: &std::path::Path) -> ClientConfig {
+    std::fs::File::open(version_dir.join("client_config.json"))
+        .ok()
+        .and_then(|file| serde_json::from_reader(file).ok())
+        .unwrap_or_default()
+}
+
+pub fn pinned_injector_build(version_dir: &std::path::Path) -> Option<u32> {
+    read_client_config(version_dir).authlib_injector_build
 }
 
 #[tauri::command]
@@ -2276,8 +2439,15 @@ pub fn find_client_for_server(app: AppHandle, server_id: i32, server_name: Optio
 }
 
 #[tauri::command]
-pub fn set_client_server_id(app: AppHandle, version_id: String, server_id: i32, game_path: Option<String>) -> Result<(), String> {
-    let mc_dir = if let Some(path) = game_path {
+pub fn set_client_server_id(
+    app: AppHandle,
+    version_id: String,
+    server_id: i32,
+    game_path: Option<String>,
+    server_name: Option<String>,
+    server_address: Option<String>
+) -> Result<(), String> {
+    let mc_dir = if let Some(path) = game_path.clone() {
         PathBuf::from(path)
     } else {
         app.path().app_data_dir().map_err(|e| e.to_string())?.join(".minecraft")
@@ -2292,9 +2462,9 @@ pub fn set_client_server_id(app: AppHandle, version_id: String, server_id: i32,
     
     let mut config = if config_path.exists() {
         let file = std::fs::File::open(&config_path).map_err(|e| e.to_string())?;
-        serde_json::from_reader(file).unwrap_or(ClientConfig { server_id: None })
+        serde_json::from_reader(file).unwrap_or_default()
     } else {
-        ClientConfig { server_id: None }
+        ClientConfig::default()
     };
 
     config.server_id = Some(server_id);
@@ -2302,5 +2472,27 @@ pub fn set_client_server_id(app: AppHandle, version_id: String, server_id: i32,
     let file = std::fs::File::create(&config_path).map_err(|e| e.to_string())?;
     serde_json::to_writer_pretty(file, &config).map_err(|e| e.to_string())?;
 
+    // Pre-fill the in-game multiplayer list with the server this client was provisioned for
+    if let Some(address) = server_address.filter(|a| !a.trim().is_empty()) {
+        let name = server_name.unwrap_or_else(|| version_id.clone());
+        crate::servers_dat::provision_server_entry(&app, &version_id, game_path, &name, &address)?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+pub fn set_authlib_injector_build(app: AppHandle, version_id: String, build_number: Option<u32>, game_path: Option<String>) -> Result<(), String> {
+    let version_dir = crate::version_path::get_version_dir(&app, &version_id, game_path)?;
+    if !version_dir.exists() {
+        return Err(format!("Version {} not found", version_id));
+    }
+
+    let mut config = read_client_config(&version_dir);
+    config.authlib_injector_build = build_number;
+
+    let file = std::fs::File::create(version_dir.join("client_config.json")).map_err(|e| e.to_string())?;
+    serde_json::to_writer_pretty(file, &config).map_err(|e| e.to_string())?;
+
     Ok(())
 }
@@ -1,9 +1,27 @@
 mod auth;
+mod auth_microsoft;
+mod auth_offline;
+mod auth_server;
+mod auth_yggdrasil;
+mod authlib_injector;
+mod bandwidth;
 mod config;
+mod content_store;
+mod download_manager;
 mod downloader;
+mod forge_installer;
+mod http_cache;
+mod lan_discovery;
 mod launcher;
 mod modrinth;
+mod motd;
+mod nbt;
+mod optifine;
+mod rcon;
 mod server_ping;
+mod server_query;
+mod servers_dat;
+mod skin;
 mod system_info;
 mod version_path;
 use tauri::Manager;
@@ -18,14 +36,6 @@ fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
-#[tauri::command]
-async fn login(auth_server: String, username: String, password: String) -> Result<auth::AuthResponse, String> {
-    match auth::authenticate(&auth_server, &username, &password).await {
-        Ok(response) => Ok(response),
-        Err(e) => Err(e.to_string()),
-    }
-}
-
 #[tauri::command]
 fn focus_window(app: tauri::AppHandle, label: String) {
     if let Some(window) = app.get_webview_window(&label) {
@@ -36,18 +46,45 @@ fn focus_window(app: tauri::AppHandle, label: String) {
 }
 
 #[tauri::command]
-async fn ping_server(host: String, port: u16) -> Result<server_ping::ServerStatus, String> {
-    match server_ping::ping_server(&host, port).await {
-        Ok(status) => Ok(status),
+async fn ping_server(
+    cache: tauri::State<'_, server_ping::PingCache>,
+    host: String,
+    port: Option<u16>,
+    protocol_version: Option<i32>,
+    samples: Option<u32>
+) -> Result<server_ping::ServerStatus, String> {
+    let defaults = server_ping::PingOptions::default();
+    let options = server_ping::PingOptions {
+        protocol_version: protocol_version.unwrap_or(defaults.protocol_version),
+        samples: samples.unwrap_or(defaults.samples).max(1),
+    };
+    match server_ping::ping_server(&host, port, options).await {
+        Ok(status) => {
+            cache.store(&host, port, &status);
+            Ok(status)
+        }
         Err(e) => Err(e.to_string()),
     }
 }
 
+#[tauri::command]
+async fn query_server(host: String, port: Option<u16>, full: Option<bool>) -> Result<server_query::QueryStatus, String> {
+    server_query::query_server(&host, port, full.unwrap_or(true)).await.map_err(|e| e.to_string())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .setup(|app| {
             app.manage(downloader::DownloadState::new());
+            app.manage(content_store::ContentStore::load(app.handle()));
+            app.manage(download_manager::DownloadManager::load(app.handle()));
+            download_manager::start(app.handle().clone());
+            app.manage(auth_server::AuthServerState::new());
+            app.manage(auth::AuthRegistry::new());
+            app.manage(server_ping::PingCache::new());
+            app.manage(lan_discovery::LanDiscoveryState::new());
+            app.manage(rcon::RconState::new());
             let window = app.get_webview_window("main").unwrap();
 
             #[cfg(target_os = "macos")]
@@ -63,13 +100,33 @@ pub fn run() {
         .plugin(tauri_plugin_dialog::init())
         .invoke_handler(tauri::generate_handler![
             greet, 
-            login,
+            auth::login,
+            auth::refresh_session,
+            auth::validate_session,
+            auth::logout,
+            auth::list_auth_providers,
+            auth_microsoft::get_microsoft_login_url,
+            auth_server::get_auth_server_metadata,
             downloader::fetch_manifest,
             downloader::fetch_loaders,
             downloader::install_version,
             downloader::download_custom_files,
             downloader::download_single_file,
             downloader::cancel_download,
+            download_manager::list_download_tasks,
+            download_manager::pause_download_task,
+            download_manager::resume_download_task,
+            download_manager::set_download_task_priority,
+            download_manager::set_max_concurrent_downloads,
+            download_manager::set_download_speed_limit,
+            download_manager::set_max_connections_per_host,
+            content_store::set_shared_store,
+            content_store::gc_shared_store,
+            http_cache::set_offline_mode,
+            http_cache::set_metadata_cache_ttl,
+            optifine::fetch_optifine_versions,
+            optifine::install_optifine,
+            optifine::set_optifine_mirror,
             launcher::launch_game,
             launcher::list_installed_versions,
             launcher::delete_version,
@@ -81,6 +138,12 @@ pub fn run() {
             launcher::get_version_details,
             launcher::find_client_for_server,
             launcher::set_client_server_id,
+            servers_dat::list_server_entries,
+            servers_dat::add_server_entry,
+            servers_dat::remove_server_entry,
+            servers_dat::move_server_entry,
+            launcher::set_authlib_injector_build,
+            authlib_injector::check_authlib_injector_update,
             launcher::open_mods_folder,
             launcher::get_version_runtime_info,
             modrinth::search_modrinth,
@@ -88,10 +151,23 @@ pub fn run() {
             modrinth::get_game_versions,
             modrinth::get_modrinth_project,
             modrinth::install_mod,
+            skin::get_profile_textures,
+            skin::upload_skin,
+            skin::reset_skin,
             config::get_app_config,
             config::set_isolation_mode,
             focus_window,
             ping_server,
+            server_ping::ping_servers,
+            query_server,
+            rcon::rcon_connect,
+            rcon::rcon_execute,
+            rcon::rcon_disconnect,
+            rcon::rcon_has_saved_password,
+            rcon::rcon_forget_password,
+            lan_discovery::start_lan_discovery,
+            lan_discovery::stop_lan_discovery,
+            lan_discovery::list_lan_games,
             system_info::get_memory_info
         ])
         .run(tauri::generate_context!())
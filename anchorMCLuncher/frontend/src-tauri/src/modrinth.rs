@@ -217,20 +217,10 @@ pub struct GameVersionTag {
 }
 
 #[command]
-pub async fn get_game_versions() -> Result<Vec<String>, String> {
+pub async fn get_game_versions(app: AppHandle) -> Result<Vec<String>, String> {
     let client = Client::new();
     let url = format!("{}/tag/game_version", MODRINTH_API_BASE);
-    
-    let response = client.get(&url)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if !response.status().is_success() {
-        return Err(format!("Modrinth API error: {}", response.status()));
-    }
-
-    let tags: Vec<GameVersionTag> = response.json().await.map_err(|e| e.to_string())?;
+    let tags: Vec<GameVersionTag> = crate::http_cache::get_json(&app, &client, &url).await?;
     
     // Filter for release versions
     let versions: Vec<String> = tags.into_iter()
@@ -0,0 +1,294 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TextStyle {
+    // Always "#rrggbb"; named colors are mapped to their vanilla values
+    pub color: Option<String>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underlined: bool,
+    pub strikethrough: bool,
+    pub obfuscated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextSpan {
+    pub text: String,
+    #[serde(flatten)]
+    pub style: TextStyle,
+}
+
+const NAMED_COLORS: [(&str, char, &str); 16] = [
+    ("black", '0', "#000000"),
+    ("dark_blue", '1', "#0000aa"),
+    ("dark_green", '2', "#00aa00"),
+    ("dark_aqua", '3', "#00aaaa"),
+    ("dark_red", '4', "#aa0000"),
+    ("dark_purple", '5', "#aa00aa"),
+    ("gold", '6', "#ffaa00"),
+    ("gray", '7', "#aaaaaa"),
+    ("dark_gray", '8', "#555555"),
+    ("blue", '9', "#5555ff"),
+    ("green", 'a', "#55ff55"),
+    ("aqua", 'b', "#55ffff"),
+    ("red", 'c', "#ff5555"),
+    ("light_purple", 'd', "#ff55ff"),
+    ("yellow", 'e', "#ffff55"),
+    ("white", 'f', "#ffffff"),
+];
+
+fn color_from_name(name: &str) -> Option<String> {
+    if name.starts_with('#') && name.len() == 7 && name[1..].chars().all(|c| c.is_ascii_hexdigit()) {
+        return Some(name.to_lowercase());
+    }
+    NAMED_COLORS.iter().find(|(n, _, _)| *n == name).map(|(_, _, hex)| hex.to_string())
+}
+
+fn color_from_code(code: char) -> Option<String> {
+    NAMED_COLORS.iter().find(|(_, c, _)| *c == code).map(|(_, _, hex)| hex.to_string())
+}
+
+// Parses a status `description`, which may be a plain string, a component object or an array
+pub fn parse_component(value: &Value) -> Vec<TextSpan> {
+    let mut spans = Vec::new();
+    push_component(value, &TextStyle::default(), &mut spans);
+    merge_spans(spans)
+}
+
+pub fn plain_text(spans: &[TextSpan]) -> String {
+    spans.iter().map(|s| s.text.as_str()).collect()
+}
+
+fn push_component(value: &Value, parent: &TextStyle, spans: &mut Vec<TextSpan>) {
+    match value {
+        Value::String(text) => push_legacy(text, parent, spans),
+        // The first element is the parent of the rest, as in vanilla
+        Value::Array(items) => {
+            let Some((first, rest)) = items.split_first() else {
+                return;
+            };
+            let style = component_style(first, parent);
+            push_component(first, parent, spans);
+            for item in rest {
+                push_component(item, &style, spans);
+            }
+        }
+        Value::Object(obj) => {
+            let style = component_style(value, parent);
+            if let Some(text) = obj.get("text").and_then(|t| t.as_str()) {
+                push_legacy(text, &style, spans);
+            } else if let Some(key) = obj.get("translate").and_then(|t| t.as_str()) {
+                push_legacy(&translate(obj, key), &style, spans);
+            }
+            if let Some(extra) = obj.get("extra").and_then(|e| e.as_array()) {
+                for item in extra {
+                    push_component(item, &style, spans);
+                }
+            }
+        }
+        Value::Number(n) => push_legacy(&n.to_string(), parent, spans),
+        Value::Bool(b) => push_legacy(&b.to_string(), parent, spans),
+        Value::Null => {}
+    }
+}
+
+fn component_style(value: &Value, parent: &TextStyle) -> TextStyle {
+    let mut style = parent.clone();
+    let Some(obj) = value.as_object() else {
+        return style;
+    };
+    if let Some(color) = obj.get("color").and_then(|c| c.as_str()).and_then(color_from_name) {
+        style.color = Some(color);
+    }
+    let flag = |key: &str, current: bool| obj.get(key).and_then(|v| v.as_bool()).unwrap_or(current);
+    style.bold = flag("bold", style.bold);
+    style.italic = flag("italic", style.italic);
+    style.underlined = flag("underlined", style.underlined);
+    style.strikethrough = flag("strikethrough", style.strikethrough);
+    style.obfuscated = flag("obfuscated", style.obfuscated);
+    style
+}
+
+// No language files here, so use the server-provided fallback or the key with its arguments filled in
+fn translate(obj: &serde_json::Map<String, Value>, key: &str) -> String {
+    let template = obj.get("fallback").and_then(|f| f.as_str()).unwrap_or(key);
+    let args: Vec<String> = obj
+        .get("with")
+        .and_then(|w| w.as_array())
+        .map(|with| with.iter().map(|arg| plain_text(&parse_component(arg))).collect())
+        .unwrap_or_default();
+
+    let mut result = String::new();
+    let mut next_arg = 0;
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        match chars.peek().copied() {
+            Some('%') => {
+                chars.next();
+                result.push('%');
+            }
+            Some('s') => {
+                chars.next();
+                result.push_str(args.get(next_arg).map(|a| a.as_str()).unwrap_or(""));
+                next_arg += 1;
+            }
+            Some(d) if d.is_ascii_digit() => {
+                // Positional form: %1$s
+                let mut index = String::new();
+                while let Some(d) = chars.peek().copied().filter(|d| d.is_ascii_digit()) {
+                    index.push(d);
+                    chars.next();
+                }
+                if chars.next() == Some('$') && chars.next() == Some('s') {
+                    let i = index.parse::<usize>().unwrap_or(0).saturating_sub(1);
+                    result.push_str(args.get(i).map(|a| a.as_str()).unwrap_or(""));
+                }
+            }
+            _ => result.push('%'),
+        }
+    }
+    result
+}
+
+// Applies `§` codes on top of `base`, including the `§x§r§r§g§g§b§b` hex form
+fn push_legacy(text: &str, base: &TextStyle, spans: &mut Vec<TextSpan>) {
+    let mut style = base.clone();
+    let mut current = String::new();
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] != '\u{a7}' || i + 1 >= chars.len() {
+            current.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        let code = chars[i + 1].to_ascii_lowercase();
+        let mut consumed = 2;
+        let mut next = style.clone();
+        match code {
+            'x' => {
+                let digits: Option<String> = (0..6)
+                    .map(|n| {
+                        let at = i + 2 + n * 2;
+                        match (chars.get(at), chars.get(at + 1)) {
+                            (Some('\u{a7}'), Some(d)) if d.is_ascii_hexdigit() => Some(d.to_ascii_lowercase()),
+                            _ => None,
+                        }
+                    })
+                    .collect();
+                if let Some(digits) = digits {
+                    next = TextStyle {
+                        color: Some(format!("#{}", digits)),
+                        ..TextStyle::default()
+                    };
+                    consumed = 14;
+                }
+            }
+            'k' => next.obfuscated = true,
+            'l' => next.bold = true,
+            'm' => next.strikethrough = true,
+            'n' => next.underlined = true,
+            'o' => next.italic = true,
+            'r' => next = base.clone(),
+            // A color code also clears any formatting before it
+            c => match color_from_code(c) {
+                Some(color) => {
+                    next = TextStyle {
+                        color: Some(color),
+                        ..TextStyle::default()
+                    }
+                }
+                None => consumed = 0,
+            },
+        }
+
+        if consumed == 0 {
+            // Unknown codes are swallowed, as the game does
+            i += 2;
+            continue;
+        }
+        if next != style && !current.is_empty() {
+            spans.push(TextSpan {
+                text: std::mem::take(&mut current),
+                style: style.clone(),
+            });
+        }
+        style = next;
+        i += consumed;
+    }
+    if !current.is_empty() {
+        spans.push(TextSpan { text: current, style });
+    }
+}
+
+fn merge_spans(spans: Vec<TextSpan>) -> Vec<TextSpan> {
+    let mut merged: Vec<TextSpan> = Vec::with_capacity(spans.len());
+    for span in spans {
+        match merged.last_mut() {
+            Some(last) if last.style == span.style => last.text.push_str(&span.text),
+            _ => merged.push(span),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn span(text: &str, color: Option<&str>, bold: bool, italic: bool) -> (String, TextStyle) {
+        let style = TextStyle {
+            color: color.map(|c| c.to_string()),
+            bold,
+            italic,
+            ..TextStyle::default()
+        };
+        (text.to_string(), style)
+    }
+
+    fn flatten(spans: Vec<TextSpan>) -> Vec<(String, TextStyle)> {
+        spans.into_iter().map(|s| (s.text, s.style)).collect()
+    }
+
+    #[test]
+    fn nested_components_inherit_and_legacy_codes_reset() {
+        let description = json!({
+            "text": "A",
+            "color": "gold",
+            "bold": true,
+            "extra": ["\u{a7}cB", { "text": "C", "italic": true }, "D", "E"]
+        });
+        assert_eq!(
+            flatten(parse_component(&description)),
+            vec![
+                span("A", Some("#ffaa00"), true, false),
+                // A color code drops the inherited bold
+                span("B", Some("#ff5555"), false, false),
+                span("C", Some("#ffaa00"), true, true),
+                // Adjacent spans with the same style are merged
+                span("DE", Some("#ffaa00"), true, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn legacy_string_formatting_and_reset() {
+        let description = json!("\u{a7}6Gold \u{a7}lBold\u{a7}r plain \u{a7}x\u{a7}1\u{a7}2\u{a7}3\u{a7}4\u{a7}5\u{a7}6hex");
+        assert_eq!(
+            flatten(parse_component(&description)),
+            vec![
+                span("Gold ", Some("#ffaa00"), false, false),
+                span("Bold", Some("#ffaa00"), true, false),
+                span(" plain ", None, false, false),
+                span("hex", Some("#123456"), false, false),
+            ]
+        );
+    }
+}
@@ -0,0 +1,311 @@
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    // Element type id is kept so empty lists round-trip unchanged
+    List(u8, Vec<Tag>),
+    // Entry order is kept as written by the game
+    Compound(Vec<(String, Tag)>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+const TAG_END: u8 = 0;
+
+impl Tag {
+    pub fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => 1,
+            Tag::Short(_) => 2,
+            Tag::Int(_) => 3,
+            Tag::Long(_) => 4,
+            Tag::Float(_) => 5,
+            Tag::Double(_) => 6,
+            Tag::ByteArray(_) => 7,
+            Tag::String(_) => 8,
+            Tag::List(_, _) => 9,
+            Tag::Compound(_) => 10,
+            Tag::IntArray(_) => 11,
+            Tag::LongArray(_) => 12,
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Tag> {
+        match self {
+            Tag::Compound(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    // Replaces an existing entry in place, or appends it
+    pub fn set(&mut self, key: &str, value: Tag) {
+        if let Tag::Compound(entries) = self {
+            match entries.iter_mut().find(|(k, _)| k == key) {
+                Some(entry) => entry.1 = value,
+                None => entries.push((key.to_string(), value)),
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        if let Tag::Compound(entries) = self {
+            entries.retain(|(k, _)| k != key);
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Tag::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_byte(&self) -> Option<i8> {
+        match self {
+            Tag::Byte(b) => Some(*b),
+            _ => None,
+        }
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self.pos.checked_add(len).filter(|end| *end <= self.data.len()).ok_or("Unexpected end of NBT data")?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], String> {
+        let mut buf = [0; N];
+        buf.copy_from_slice(self.take(N)?);
+        Ok(buf)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn len(&mut self) -> Result<usize, String> {
+        let len = i32::from_be_bytes(self.array()?);
+        usize::try_from(len).map_err(|_| "Negative NBT length".to_string())
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let len = u16::from_be_bytes(self.array()?) as usize;
+        Ok(decode_mutf8(self.take(len)?))
+    }
+
+    fn payload(&mut self, id: u8, depth: usize) -> Result<Tag, String> {
+        if depth > 512 {
+            return Err("NBT nested too deeply".to_string());
+        }
+        Ok(match id {
+            1 => Tag::Byte(self.u8()? as i8),
+            2 => Tag::Short(i16::from_be_bytes(self.array()?)),
+            3 => Tag::Int(i32::from_be_bytes(self.array()?)),
+            4 => Tag::Long(i64::from_be_bytes(self.array()?)),
+            5 => Tag::Float(f32::from_be_bytes(self.array()?)),
+            6 => Tag::Double(f64::from_be_bytes(self.array()?)),
+            7 => {
+                let len = self.len()?;
+                Tag::ByteArray(self.take(len)?.iter().map(|b| *b as i8).collect())
+            }
+            8 => Tag::String(self.string()?),
+            9 => {
+                let element = self.u8()?;
+                let len = self.len()?;
+                let mut items = Vec::new();
+                for _ in 0..len {
+                    items.push(self.payload(element, depth + 1)?);
+                }
+                Tag::List(element, items)
+            }
+            10 => {
+                let mut entries = Vec::new();
+                loop {
+                    let id = self.u8()?;
+                    if id == TAG_END {
+                        break;
+                    }
+                    let name = self.string()?;
+                    entries.push((name, self.payload(id, depth + 1)?));
+                }
+                Tag::Compound(entries)
+            }
+            11 => {
+                let len = self.len()?;
+                let mut items = Vec::new();
+                for _ in 0..len {
+                    items.push(i32::from_be_bytes(self.array()?));
+                }
+                Tag::IntArray(items)
+            }
+            12 => {
+                let len = self.len()?;
+                let mut items = Vec::new();
+                for _ in 0..len {
+                    items.push(i64::from_be_bytes(self.array()?));
+                }
+                Tag::LongArray(items)
+            }
+            other => return Err(format!("Unknown NBT tag type {}", other)),
+        })
+    }
+}
+
+// Reads uncompressed big-endian NBT (servers.dat is not gzipped), returning the root name and tag
+pub fn read(data: &[u8]) -> Result<(String, Tag), String> {
+    let mut reader = Reader { data, pos: 0 };
+    let id = reader.u8()?;
+    if id == TAG_END {
+        return Err("NBT data has no root tag".to_string());
+    }
+    let name = reader.string()?;
+    let tag = reader.payload(id, 0)?;
+    Ok((name, tag))
+}
+
+pub fn write(name: &str, tag: &Tag) -> Vec<u8> {
+    let mut buf = vec![tag.id()];
+    write_string(&mut buf, name);
+    write_payload(&mut buf, tag);
+    buf
+}
+
+fn write_string(buf: &mut Vec<u8>, s: &str) {
+    let encoded = encode_mutf8(s);
+    buf.extend_from_slice(&(encoded.len() as u16).to_be_bytes());
+    buf.extend_from_slice(&encoded);
+}
+
+fn write_payload(buf: &mut Vec<u8>, tag: &Tag) {
+    match tag {
+        Tag::Byte(v) => buf.push(*v as u8),
+        Tag::Short(v) => buf.extend_from_slice(&v.to_be_bytes()),
+        Tag::Int(v) => buf.extend_from_slice(&v.to_be_bytes()),
+        Tag::Long(v) => buf.extend_from_slice(&v.to_be_bytes()),
+        Tag::Float(v) => buf.extend_from_slice(&v.to_be_bytes()),
+        Tag::Double(v) => buf.extend_from_slice(&v.to_be_bytes()),
+        Tag::ByteArray(items) => {
+            buf.extend_from_slice(&(items.len() as i32).to_be_bytes());
+            buf.extend(items.iter().map(|b| *b as u8));
+        }
+        Tag::String(s) => write_string(buf, s),
+        Tag::List(element, items) => {
+            let element = items.first().map(|t| t.id()).unwrap_or(*element);
+            buf.push(element);
+            buf.extend_from_slice(&(items.len() as i32).to_be_bytes());
+            for item in items {
+                write_payload(buf, item);
+            }
+        }
+        Tag::Compound(entries) => {
+            for (name, value) in entries {
+                buf.push(value.id());
+                write_string(buf, name);
+                write_payload(buf, value);
+            }
+            buf.push(TAG_END);
+        }
+        Tag::IntArray(items) => {
+            buf.extend_from_slice(&(items.len() as i32).to_be_bytes());
+            for v in items {
+                buf.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+        Tag::LongArray(items) => {
+            buf.extend_from_slice(&(items.len() as i32).to_be_bytes());
+            for v in items {
+                buf.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+    }
+}
+
+// Java's modified UTF-8: NUL takes two bytes and supplementary characters are encoded as surrogate pairs
+fn encode_mutf8(s: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(s.len());
+    for unit in s.encode_utf16() {
+        match unit {
+            0x0001..=0x007F => out.push(unit as u8),
+            0x0000 | 0x0080..=0x07FF => {
+                out.push(0xC0 | (unit >> 6) as u8);
+                out.push(0x80 | (unit & 0x3F) as u8);
+            }
+            _ => {
+                out.push(0xE0 | (unit >> 12) as u8);
+                out.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+                out.push(0x80 | (unit & 0x3F) as u8);
+            }
+        }
+    }
+    out
+}
+
+fn decode_mutf8(bytes: &[u8]) -> String {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i] as u16;
+        if b & 0x80 == 0 {
+            units.push(b);
+            i += 1;
+        } else if b & 0xE0 == 0xC0 && i + 1 < bytes.len() {
+            units.push(((b & 0x1F) << 6) | (bytes[i + 1] as u16 & 0x3F));
+            i += 2;
+        } else if b & 0xF0 == 0xE0 && i + 2 < bytes.len() {
+            units.push(((b & 0x0F) << 12) | ((bytes[i + 1] as u16 & 0x3F) << 6) | (bytes[i + 2] as u16 & 0x3F));
+            i += 3;
+        } else {
+            units.push(0xFFFD);
+            i += 1;
+        }
+    }
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_servers_dat_shapes() {
+        let root = Tag::Compound(vec![
+            (
+                "servers".to_string(),
+                Tag::List(
+                    10,
+                    vec![Tag::Compound(vec![
+                        ("name".to_string(), Tag::String("Sérveur \0 😀".to_string())),
+                        ("ip".to_string(), Tag::String("mc.example.net:25565".to_string())),
+                        ("acceptTextures".to_string(), Tag::Byte(1)),
+                    ])],
+                ),
+            ),
+            ("empty".to_string(), Tag::List(8, Vec::new())),
+            ("numbers".to_string(), Tag::List(3, vec![Tag::Int(-1), Tag::Int(i32::MAX)])),
+            ("longs".to_string(), Tag::LongArray(vec![i64::MIN, 0])),
+            ("bytes".to_string(), Tag::ByteArray(vec![-128, 127])),
+            ("ratio".to_string(), Tag::Double(0.5)),
+        ]);
+
+        let data = write("", &root);
+        assert_eq!(read(&data).unwrap(), (String::new(), root.clone()));
+        // Modified UTF-8 never contains a raw NUL and writes astral characters as surrogate pairs
+        assert!(!encode_mutf8("\0").contains(&0));
+        assert_eq!(encode_mutf8("😀").len(), 6);
+        assert_eq!(write("", &read(&data).unwrap().1), data);
+    }
+}
@@ -0,0 +1,269 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::AppHandle;
+
+use crate::download_manager::TaskFile;
+use crate::forge_installer::{log, maven_path, read_version_json, status, write_version_json, InstallerJar};
+
+const DEFAULT_MIRROR: &str = "https://bmclapi2.bangbang93.com";
+const LIBRARIES_URL: &str = "https://libraries.minecraft.net/";
+const LAUNCHWRAPPER: &str = "net.minecraft:launchwrapper:1.12";
+const LAUNCHWRAPPER_MAIN: &str = "net.minecraft.launchwrapper.Launch";
+const TWEAKER: &str = "optifine.OptiFineTweaker";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptiFineBuild {
+    pub mcversion: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub patch: String,
+    pub filename: String,
+    // "Forge 14.23.5.2847" names the Forge build it was made for, "Forge N/A" means it can't run under Forge
+    #[serde(default)]
+    pub forge: Option<String>,
+}
+
+impl OptiFineBuild {
+    // "HD_U_G5", what the UI offers as the loader version
+    pub fn id(&self) -> String {
+        format!("{}_{}", self.kind, self.patch)
+    }
+
+    // The name comes from the mirror, so anything but a bare file name is refused
+    fn file_name(&self) -> Result<&str, String> {
+        Path::new(&self.filename)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .filter(|name| *name == self.filename && !name.contains(['/', '\\']))
+            .ok_or_else(|| format!("Invalid OptiFine file name: {}", self.filename))
+    }
+
+    fn supports_forge(&self) -> bool {
+        self.forge.as_deref().is_none_or(|forge| !forge.contains("N/A"))
+    }
+
+    fn required_forge(&self) -> Option<&str> {
+        let version = self.forge.as_deref()?.trim_start_matches("Forge").trim().trim_start_matches('#');
+        version.starts_with(|c: char| c.is_ascii_digit()).then_some(version)
+    }
+}
+
+fn mirror() -> String {
+    crate::config::load_config()
+        .optifine_mirror
+        .filter(|m| !m.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_MIRROR.to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+// Older compatibility data only gives the Forge build number, which is the last segment of the full version
+fn forge_at_least(installed: &str, required: &str) -> bool {
+    let numbers = |v: &str| -> Vec<u64> { v.split(['.', '-']).filter_map(|part| part.parse().ok()).collect() };
+    let (installed, required) = (numbers(installed), numbers(required));
+    if required.len() == 1 {
+        return installed.last().is_some_and(|build| *build >= required[0]);
+    }
+    installed >= required
+}
+
+pub async fn fetch_builds(app: &AppHandle, client: &reqwest::Client, game_version: &str) -> Result<Vec<OptiFineBuild>, String> {
+    let url = format!("{}/optifine/{}", mirror(), game_version);
+    crate::http_cache::get_json(app, client, &url).await
+}
+
+async fn find_build(app: &AppHandle, client: &reqwest::Client, game_version: &str, optifine_version: &str) -> Result<OptiFineBuild, String> {
+    fetch_builds(app, client, game_version)
+        .await?
+        .into_iter()
+        .find(|build| build.id() == optifine_version)
+        .ok_or_else(|| format!("OptiFine {} is not available for {}", optifine_version, game_version))
+}
+
+async fn download_build(app: &AppHandle, build: &OptiFineBuild, target: &Path, task_id: &Option<String>) -> Result<(), String> {
+    let url = format!("{}/optifine/{}/{}/{}", mirror(), build.mcversion, build.kind, build.patch);
+    crate::downloader::download_files(app, &build.mcversion, vec![TaskFile::new(url, target.to_path_buf(), None)], task_id.clone()).await
+}
+
+// Builds a "<mc>-OptiFine_<type>_<patch>" version that starts the game through launchwrapper and OptiFine's tweaker
+pub async fn install(
+    app: &AppHandle,
+    client: &reqwest::Client,
+    game_version: &str,
+    optifine_version: &str,
+    mc_dir: &Path,
+    java_path: Option<String>,
+    task_id: Option<String>
+) -> Result<String, String> {
+    let build = find_build(app, client, game_version, optifine_version).await?;
+    let installer_path = std::env::temp_dir().join(build.file_name()?);
+    download_build(app, &build, &installer_path, &task_id).await?;
+    let result = install_standalone(app, &build, &installer_path, mc_dir, java_path, task_id).await;
+    let _ = fs::remove_file(&installer_path);
+    result
+}
+
+async fn install_standalone(
+    app: &AppHandle,
+    build: &OptiFineBuild,
+    installer_path: &Path,
+    mc_dir: &Path,
+    java_path: Option<String>,
+    task_id: Option<String>
+) -> Result<String, String> {
+    let mc = build.mcversion.as_str();
+    let parent = read_version_json(mc_dir, mc)?;
+    let libraries_dir = mc_dir.join("libraries");
+    let library = format!("optifine:OptiFine:{}_{}", mc, build.id());
+    let library_path = libraries_dir.join(maven_path(&library).ok_or("Invalid OptiFine version")?);
+    if let Some(dir) = library_path.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+
+    let mut installer = InstallerJar::open(installer_path)?;
+    // Newer builds ship as a delta against the vanilla jar and have to be patched into the real library
+    if installer.has("optifine/Patcher.class") {
+        let java = java_path.filter(|p| !p.is_empty()).unwrap_or_else(|| "java".to_string());
+        let mc_jar = mc_dir.join("versions").join(mc).join(format!("{}.jar", mc));
+        let (installer_path, library_path) = (installer_path.to_path_buf(), library_path.clone());
+        status(app, &task_id, mc, "Patching OptiFine...".to_string(), 0, 1);
+        let output = tauri::async_runtime::spawn_blocking(move || {
+            Command::new(&java)
+                .arg("-cp")
+                .arg(&installer_path)
+                .arg("optifine.Patcher")
+                .arg(&mc_jar)
+                .arg(&installer_path)
+                .arg(&library_path)
+                .output()
+        })
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to start the OptiFine patcher: {}", e))?;
+        for line in String::from_utf8_lossy(&output.stderr).lines() {
+            log(app, &task_id, "error", line.to_string());
+        }
+        if !output.status.success() {
+            return Err(format!("OptiFine patcher failed with status: {}", output.status));
+        }
+    } else {
+        fs::copy(installer_path, &library_path).map_err(|e| e.to_string())?;
+    }
+
+    let mut libraries = vec![json!({ "name": library })];
+    let mut queue = Vec::new();
+    // Builds for 1.13+ bundle their own launchwrapper fork
+    match installer.read("launchwrapper-of.txt") {
+        Ok(raw) => {
+            let version = String::from_utf8_lossy(&raw).trim().to_string();
+            let coords = format!("optifine:launchwrapper-of:{}", version);
+            let path = maven_path(&coords).ok_or("Invalid launchwrapper version")?;
+            installer.extract(&format!("launchwrapper-of-{}.jar", version), &libraries_dir.join(path))?;
+            libraries.push(json!({ "name": coords }));
+        }
+        Err(_) => {
+            let path = maven_path(LAUNCHWRAPPER).ok_or("Invalid launchwrapper version")?;
+            queue.push(TaskFile::new(format!("{}{}", LIBRARIES_URL, path), libraries_dir.join(path), None));
+            libraries.push(json!({ "name": LAUNCHWRAPPER }));
+        }
+    }
+
+    let mut version_json = json!({
+        "id": format!("{}-OptiFine_{}", mc, build.id()),
+        "inheritsFrom": mc,
+        "jar": mc,
+        "type": parent["type"].as_str().unwrap_or("release"),
+        "mainClass": LAUNCHWRAPPER_MAIN,
+        "libraries": libraries,
+    });
+    // minecraftArguments replaces the parent's string, while 1.13+ arguments are appended to it
+    match parent["minecraftArguments"].as_str() {
+        Some(args) => version_json["minecraftArguments"] = json!(format!("{} --tweakClass {}", args, TWEAKER)),
+        None => version_json["arguments"] = json!({ "game": ["--tweakClass", TWEAKER] }),
+    }
+    let version_id = write_version_json(mc_dir, &version_json)?;
+
+    crate::downloader::download_files(app, mc, queue, task_id.clone()).await?;
+    status(app, &task_id, &version_id, "Installation Complete".to_string(), 1, 1);
+    Ok(version_id)
+}
+
+// Puts the jar into a Forge instance's mods folder, replacing any other OptiFine build there
+async fn install_into_mods(app: &AppHandle, build: &OptiFineBuild, mods_dir: &PathBuf, task_id: Option<String>) -> Result<(), String> {
+    let file_name = build.file_name()?;
+    fs::create_dir_all(mods_dir).map_err(|e| e.to_string())?;
+    if let Ok(entries) = fs::read_dir(mods_dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_optifine = name.starts_with("OptiFine_") || name.starts_with("preview_OptiFine_");
+            if is_optifine && name.ends_with(".jar") && name != file_name {
+                log(app, &task_id, "info", format!("Removing {}", name));
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+    download_build(app, build, &mods_dir.join(file_name), &task_id).await?;
+    status(app, &task_id, &build.mcversion, "Installation Complete".to_string(), 1, 1);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn fetch_optifine_versions(app: AppHandle, game_version: String) -> Result<Vec<OptiFineBuild>, String> {
+    let client = reqwest::Client::new();
+    fetch_builds(&app, &client, &game_version).await
+}
+
+// Adds OptiFine to an installed version: Forge instances get the jar as a mod when the build supports their
+// Forge, anything else gets a standalone OptiFine version. Returns the version to launch.
+#[tauri::command]
+pub async fn install_optifine(
+    app: AppHandle,
+    version_id: String,
+    optifine_version: String,
+    game_path: Option<String>,
+    java_path: Option<String>,
+    task_id: Option<String>
+) -> Result<String, String> {
+    let info = crate::launcher::get_version_runtime_info(app.clone(), version_id.clone(), game_path.clone())?;
+    let game_version = info.mc_version.ok_or("Cannot tell the game version of this version")?;
+    let mc_dir = crate::version_path::get_game_root(&app, game_path.clone())?;
+    let client = reqwest::Client::new();
+
+    match info.loader_type.as_deref() {
+        Some("forge") => {
+            let build = find_build(&app, &client, &game_version, &optifine_version).await?;
+            let forge_ok = build.supports_forge()
+                && match (build.required_forge(), info.loader_version.as_deref()) {
+                    (Some(required), Some(installed)) => forge_at_least(installed, required),
+                    _ => true,
+                };
+            if forge_ok {
+                let details = crate::launcher::get_version_details(app.clone(), version_id.clone(), game_path.clone())?;
+                let config = crate::config::load_config();
+                let isolated = crate::config::should_isolate(&config.isolation_mode, details.is_modded, &details.version_type);
+                let mods_dir = crate::version_path::get_mods_dir(&app, &version_id, game_path, isolated)?;
+                install_into_mods(&app, &build, &mods_dir, task_id).await?;
+                return Ok(version_id);
+            }
+            log(
+                &app,
+                &task_id,
+                "warn",
+                format!("OptiFine {} does not support this Forge ({}), installing it as a separate version", build.id(), build.forge.as_deref().unwrap_or("unknown")),
+            );
+            install(&app, &client, &game_version, &optifine_version, &mc_dir, java_path, task_id).await
+        }
+        Some("vanilla") | None => install(&app, &client, &game_version, &optifine_version, &mc_dir, java_path, task_id).await,
+        Some(other) => Err(format!("OptiFine cannot be added to {} versions", other)),
+    }
+}
+
+#[tauri::command]
+pub fn set_optifine_mirror(url: Option<String>) -> Result<(), String> {
+    let mut config = crate::config::load_config();
+    config.optifine_mirror = url.filter(|u| !u.trim().is_empty());
+    crate::config::save_config(&config)
+}
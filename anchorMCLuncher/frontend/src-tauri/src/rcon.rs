@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::State;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+
+const KEYRING_SERVICE: &str = "AMCLauncher-rcon";
+const TYPE_RESPONSE: i32 = 0;
+const TYPE_COMMAND: i32 = 2;
+const TYPE_AUTH: i32 = 3;
+// Any type the server doesn't know; its "Unknown request" reply marks the end of a response
+const TYPE_END_MARKER: i32 = 200;
+const RCON_TIMEOUT: Duration = Duration::from_secs(10);
+// Minecraft rejects bodies over 1446 bytes
+const MAX_COMMAND_LEN: usize = 1446;
+
+struct Packet {
+    id: i32,
+    kind: i32,
+    body: String,
+}
+
+struct RconSession {
+    stream: TcpStream,
+    next_id: i32,
+}
+
+impl RconSession {
+    async fn connect(host: &str, port: u16, password: &str) -> Result<Self, String> {
+        let stream = tokio::time::timeout(RCON_TIMEOUT, TcpStream::connect((host, port)))
+            .await
+            .map_err(|_| "RCON connection timed out".to_string())?
+            .map_err(|e| e.to_string())?;
+        let mut session = Self { stream, next_id: 1 };
+
+        let id = session.send(TYPE_AUTH, password).await?;
+        loop {
+            let packet = session.read().await?;
+            // A failed login answers with id -1; some servers send an empty response first
+            if packet.id == -1 {
+                return Err("RCON authentication failed".to_string());
+            }
+            if packet.id == id && packet.kind == TYPE_COMMAND {
+                return Ok(session);
+            }
+        }
+    }
+
+    async fn send(&mut self, kind: i32, body: &str) -> Result<i32, String> {
+        let id = self.next_id;
+        self.next_id = self.next_id.checked_add(1).unwrap_or(1);
+
+        // Length covers id, type, body and the two NUL terminators
+        let mut packet = Vec::with_capacity(body.len() + 14);
+        packet.extend_from_slice(&(body.len() as i32 + 10).to_le_bytes());
+        packet.extend_from_slice(&id.to_le_bytes());
+        packet.extend_from_slice(&kind.to_le_bytes());
+        packet.extend_from_slice(body.as_bytes());
+        packet.extend_from_slice(&[0, 0]);
+        self.stream.write_all(&packet).await.map_err(|e| e.to_string())?;
+        Ok(id)
+    }
+
+    async fn read(&mut self) -> Result<Packet, String> {
+        let read = async {
+            let len = self.stream.read_i32_le().await?;
+            if !(10..=4096 + 10).contains(&len) {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid RCON packet length"));
+            }
+            let mut buf = vec![0; len as usize];
+            self.stream.read_exact(&mut buf).await?;
+            Ok(buf)
+        };
+        let buf = tokio::time::timeout(RCON_TIMEOUT, read)
+            .await
+            .map_err(|_| "RCON response timed out".to_string())?
+            .map_err(|e| e.to_string())?;
+
+        let id = i32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]);
+        let kind = i32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]);
+        let body = String::from_utf8_lossy(&buf[8..buf.len() - 2]).to_string();
+        Ok(Packet { id, kind, body })
+    }
+
+    // Long outputs arrive split over several packets; collect them until the marker reply shows up
+    async fn execute(&mut self, command: &str) -> Result<String, String> {
+        let id = self.send(TYPE_COMMAND, command).await?;
+        self.stream.flush().await.map_err(|e| e.to_string())?;
+
+        // The marker only goes out once the command is answered, so the server never reads both as one packet
+        let mut output = String::new();
+        loop {
+            let packet = self.read().await?;
+            if packet.id == id && packet.kind == TYPE_RESPONSE {
+                output.push_str(&packet.body);
+                break;
+            }
+        }
+        let marker = self.send(TYPE_END_MARKER, "").await?;
+        self.stream.flush().await.map_err(|e| e.to_string())?;
+        loop {
+            let packet = self.read().await?;
+            if packet.id == marker {
+                return Ok(output);
+            }
+            if packet.id == id && packet.kind == TYPE_RESPONSE {
+                output.push_str(&packet.body);
+            }
+        }
+    }
+}
+
+// One authenticated connection per server, reused across commands
+pub struct RconState {
+    sessions: Mutex<HashMap<String, Arc<Mutex<RconSession>>>>,
+}
+
+impl RconState {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+fn server_key(host: &str, port: u16) -> String {
+    format!("{}:{}", host.to_lowercase(), port)
+}
+
+// Passwords live in the OS credential store (Keychain, Credential Manager, Secret Service)
+fn keyring_entry(host: &str, port: u16) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, &server_key(host, port)).map_err(|e| e.to_string())
+}
+
+fn stored_password(host: &str, port: u16) -> Result<Option<String>, String> {
+    match keyring_entry(host, port)?.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+async fn open_session(state: &RconState, host: &str, port: u16, password: &str) -> Result<Arc<Mutex<RconSession>>, String> {
+    let session = Arc::new(Mutex::new(RconSession::connect(host, port, password).await?));
+    state.sessions.lock().await.insert(server_key(host, port), session.clone());
+    Ok(session)
+}
+
+#[tauri::command]
+pub async fn rcon_connect(
+    state: State<'_, RconState>,
+    host: String,
+    port: u16,
+    password: Option<String>,
+    remember: Option<bool>
+) -> Result<(), String> {
+    let password = match password {
+        Some(password) => password,
+        None => stored_password(&host, port)?.ok_or("No saved RCON password for this server")?,
+    };
+    open_session(&state, &host, port, &password).await?;
+    // Only keep passwords that actually worked
+    if remember.unwrap_or(false) {
+        keyring_entry(&host, port)?.set_password(&password).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn rcon_execute(state: State<'_, RconState>, host: String, port: u16, command: String) -> Result<String, String> {
+    if command.len() > MAX_COMMAND_LEN {
+        return Err(format!("RCON commands are limited to {} bytes", MAX_COMMAND_LEN));
+    }
+    let key = server_key(&host, port);
+    let existing = state.sessions.lock().await.get(&key).cloned();
+    let session = match existing {
+        Some(session) => session,
+        None => {
+            let password = stored_password(&host, port)?.ok_or("Not connected and no saved RCON password")?;
+            open_session(&state, &host, port, &password).await?
+        }
+    };
+
+    let result = session.lock().await.execute(&command).await;
+    if result.is_err() {
+        // The connection is in an unknown state now; the next call reconnects
+        state.sessions.lock().await.remove(&key);
+    }
+    result
+}
+
+#[tauri::command]
+pub async fn rcon_disconnect(state: State<'_, RconState>, host: String, port: u16) -> Result<(), String> {
+    if let Some(session) = state.sessions.lock().await.remove(&server_key(&host, port)) {
+        let _ = session.lock().await.stream.shutdown().await;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn rcon_has_saved_password(host: String, port: u16) -> Result<bool, String> {
+    Ok(stored_password(&host, port)?.is_some())
+}
+
+#[tauri::command]
+pub fn rcon_forget_password(host: String, port: u16) -> Result<(), String> {
+    match keyring_entry(&host, port)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    }
+}
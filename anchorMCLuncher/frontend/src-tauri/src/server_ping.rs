@@ -1,57 +1,452 @@
 use serde::{Deserialize, Serialize};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use futures::stream::StreamExt;
+use tauri::{AppHandle, Emitter, State};
 use anyhow::Result;
+use async_trait::async_trait;
+use hickory_resolver::config::{NameServerConfigGroup, ResolverConfig, ResolverOpts};
+use hickory_resolver::TokioAsyncResolver;
+use crate::motd::TextSpan;
 
-#[derive(Debug, Serialize, Deserialize)]
+const DEFAULT_PORT: u16 = 25565;
+// 1.8, understood by every modern server for status requests
+pub const DEFAULT_PROTOCOL_VERSION: i32 = 47;
+// 1.6.4, sent inside the MC|PingHost payload
+const LEGACY_PROTOCOL_VERSION: u8 = 78;
+const MAX_CONCURRENT_PINGS: usize = 8;
+const STATUS_TTL: Duration = Duration::from_secs(30);
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+// A server that sent its status but never answers the Ping shouldn't cost us that status
+const PONG_TIMEOUT: Duration = Duration::from_secs(2);
+// Total budget for the extra latency samples
+const SAMPLING_DEADLINE: Duration = Duration::from_secs(5);
+
+// Which Server List Ping format the server answered
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum PingProtocol {
+    #[serde(rename = "modern")]
+    Modern,
+    #[serde(rename = "legacy_1_6")]
+    Legacy16,
+    #[serde(rename = "legacy_1_4")]
+    Legacy14,
+    #[serde(rename = "beta")]
+    Beta,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerStatus {
+    // Plain text with formatting codes stripped
     pub description: String,
+    pub description_spans: Vec<TextSpan>,
     pub players: Players,
     pub version: Version,
     pub favicon: Option<String>,
     pub latency: u128,
+    pub resolved_host: String,
+    pub resolved_port: u16,
+    pub ping_protocol: PingProtocol,
+    // Ping/Pong round trips; absent for legacy servers, which have no Ping packet
+    pub latency_stats: Option<LatencyStats>,
+    pub mod_info: Option<ModInfo>,
+    pub enforces_secure_chat: Option<bool>,
+    pub previews_chat: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub min: u128,
+    pub avg: u128,
+    pub max: u128,
+    pub samples: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PingOptions {
+    pub protocol_version: i32,
+    // Number of Ping/Pong round trips to time; the first rides on the status connection
+    pub samples: u32,
+}
+
+impl Default for PingOptions {
+    fn default() -> Self {
+        Self {
+            protocol_version: DEFAULT_PROTOCOL_VERSION,
+            samples: 1,
+        }
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Players {
     pub max: i32,
     pub online: i32,
+    #[serde(default)]
+    pub sample: Vec<PlayerSample>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerSample {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModInfo {
+    // "FML" for the 1.7 - 1.12 `modinfo` object, "FORGE" for 1.13+ `forgeData`
+    pub loader: String,
+    pub mods: Vec<ModEntry>,
+    pub fml_network_version: Option<i32>,
+    // Forge drops mods from the list when the status response would get too large
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModEntry {
+    pub id: String,
+    // None for server-only mods, which clients don't need
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Version {
     pub name: String,
     pub protocol: i32,
 }
 
-pub async fn ping_server(host: &str, port: u16) -> Result<ServerStatus> {
-    let start = std::time::Instant::now();
-    let mut stream = tokio::time::timeout(Duration::from_secs(5), TcpStream::connect((host, port))).await??;
+#[async_trait]
+pub trait SrvResolver: Send + Sync {
+    // Returns the target host and port of the best `_minecraft._tcp` record, if any
+    async fn resolve_srv(&self, host: &str) -> Result<Option<(String, u16)>>;
+}
 
-    // Handshake
+pub struct DnsSrvResolver {
+    resolver: TokioAsyncResolver,
+}
+
+impl DnsSrvResolver {
+    pub fn from_system() -> Self {
+        let resolver = TokioAsyncResolver::tokio_from_system_conf()
+            .unwrap_or_else(|_| TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default()));
+        Self { resolver }
+    }
+
+    // Queries a single plain-UDP/TCP nameserver, e.g. a local DNS stand-in
+    pub fn with_nameserver(addr: SocketAddr) -> Self {
+        let group = NameServerConfigGroup::from_ips_clear(&[addr.ip()], addr.port(), true);
+        let config = ResolverConfig::from_parts(None, vec![], group);
+        Self {
+            resolver: TokioAsyncResolver::tokio(config, ResolverOpts::default()),
+        }
+    }
+}
+
+#[async_trait]
+impl SrvResolver for DnsSrvResolver {
+    async fn resolve_srv(&self, host: &str) -> Result<Option<(String, u16)>> {
+        let lookup = match self.resolver.srv_lookup(format!("_minecraft._tcp.{}", host)).await {
+            Ok(lookup) => lookup,
+            Err(e) if matches!(e.kind(), hickory_resolver::error::ResolveErrorKind::NoRecordsFound { .. }) => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        // Lowest priority wins, higher weight breaks ties
+        let best = lookup
+            .iter()
+            .min_by(|a, b| a.priority().cmp(&b.priority()).then(b.weight().cmp(&a.weight())));
+
+        Ok(best.map(|srv| {
+            let target = srv.target().to_utf8();
+            (target.trim_end_matches('.').to_string(), srv.port())
+        }))
+    }
+}
+
+pub(crate) fn configured_resolver() -> DnsSrvResolver {
+    let dns_server = crate::config::load_config().dns_server.and_then(|s| s.parse::<SocketAddr>().ok());
+    match dns_server {
+        Some(addr) => DnsSrvResolver::with_nameserver(addr),
+        None => DnsSrvResolver::from_system(),
+    }
+}
+
+pub async fn ping_server(host: &str, port: Option<u16>, options: PingOptions) -> Result<ServerStatus> {
+    ping_server_with(&configured_resolver(), host, port, options).await
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerAddress {
+    pub host: String,
+    pub port: Option<u16>,
+}
+
+impl ServerAddress {
+    fn cache_key(&self) -> String {
+        match self.port {
+            Some(port) => format!("{}:{}", self.host.to_lowercase(), port),
+            None => self.host.to_lowercase(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PingResult {
+    pub host: String,
+    pub port: Option<u16>,
+    pub status: Option<ServerStatus>,
+    pub error: Option<String>,
+    pub cached: bool,
+}
+
+// Recent statuses (favicons included) so reopening the server list doesn't re-ping everything
+pub struct PingCache {
+    cache: Mutex<HashMap<String, (Instant, ServerStatus)>>,
+}
+
+impl PingCache {
+    pub fn new() -> Self {
+        Self {
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<ServerStatus> {
+        let cache = self.cache.lock().ok()?;
+        cache
+            .get(key)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < STATUS_TTL)
+            .map(|(_, status)| status.clone())
+    }
+
+    pub fn store(&self, host: &str, port: Option<u16>, status: &ServerStatus) {
+        let key = ServerAddress { host: host.to_string(), port }.cache_key();
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < STATUS_TTL);
+            cache.insert(key, (Instant::now(), status.clone()));
+        }
+    }
+}
+
+// Pings every address with a shared concurrency cap, emitting "server-ping-result" as each one finishes
+#[tauri::command]
+pub async fn ping_servers(
+    app: AppHandle,
+    cache: State<'_, PingCache>,
+    addresses: Vec<ServerAddress>,
+    refresh: Option<bool>
+) -> Result<Vec<PingResult>, String> {
+    let refresh = refresh.unwrap_or(false);
+    let resolver = configured_resolver();
+    let cache = cache.inner();
+
+    let mut stream = futures::stream::iter(addresses)
+        .map(|address| {
+            let resolver = &resolver;
+            async move {
+                if !refresh {
+                    if let Some(status) = cache.get(&address.cache_key()) {
+                        return PingResult {
+                            host: address.host,
+                            port: address.port,
+                            status: Some(status),
+                            error: None,
+                            cached: true,
+                        };
+                    }
+                }
+                match ping_server_with(resolver, &address.host, address.port, PingOptions::default()).await {
+                    Ok(status) => {
+                        cache.store(&address.host, address.port, &status);
+                        PingResult {
+                            host: address.host,
+                            port: address.port,
+                            status: Some(status),
+                            error: None,
+                            cached: false,
+                        }
+                    }
+                    Err(e) => PingResult {
+                        host: address.host,
+                        port: address.port,
+                        status: None,
+                        error: Some(e.to_string()),
+                        cached: false,
+                    },
+                }
+            }
+        })
+        .buffer_unordered(MAX_CONCURRENT_PINGS);
+
+    let mut results = Vec::new();
+    while let Some(result) = stream.next().await {
+        let _ = app.emit("server-ping-result", &result);
+        results.push(result);
+    }
+    Ok(results)
+}
+
+pub(crate) async fn resolve_address(resolver: &dyn SrvResolver, host: &str, port: Option<u16>) -> (String, u16) {
+    if let Some(port) = port {
+        return (host.to_string(), port);
+    }
+    // Like the game, only bare hostnames go through SRV; a failed lookup is not fatal
+    if host.parse::<IpAddr>().is_err() {
+        if let Ok(Some(target)) = resolver.resolve_srv(host).await {
+            return target;
+        }
+    }
+    (host.to_string(), DEFAULT_PORT)
+}
+
+pub async fn ping_server_with(resolver: &dyn SrvResolver, host: &str, port: Option<u16>, options: PingOptions) -> Result<ServerStatus> {
+    let (resolved_host, resolved_port) = resolve_address(resolver, host, port).await;
+    let host = resolved_host.as_str();
+    let port = resolved_port;
+
+    // An unreachable server fails every format the same way, so don't retry those
+    let stream = connect(host, port).await?;
+    let modern_err = match ping_modern(stream, host, port, options.protocol_version).await {
+        Ok((mut status, first_rtt)) => {
+            if let Some(first_rtt) = first_rtt {
+                let stats = sample_latency(host, port, options, first_rtt).await;
+                status.latency = stats.avg;
+                status.latency_stats = Some(stats);
+            }
+            return Ok(status.resolved(host, port));
+        }
+        Err(e) => e,
+    };
+    // A server that accepts connections but never answers would time out every format in turn,
+    // stalling the list; legacy servers reject the modern handshake quickly instead
+    if modern_err.is::<tokio::time::error::Elapsed>() {
+        return Err(modern_err);
+    }
+
+    // Pre-1.7 servers drop the modern handshake; each legacy format needs a fresh connection
+    for protocol in [PingProtocol::Legacy16, PingProtocol::Legacy14, PingProtocol::Beta] {
+        let Ok(stream) = connect(host, port).await else {
+            break;
+        };
+        if let Ok(status) = timed(ping_legacy(stream, host, port, protocol)).await {
+            return Ok(status.resolved(host, port));
+        }
+    }
+    Err(modern_err)
+}
+
+async fn connect(host: &str, port: u16) -> Result<TcpStream> {
+    Ok(tokio::time::timeout(PING_TIMEOUT, TcpStream::connect((host, port))).await??)
+}
+
+async fn timed<T>(fut: impl std::future::Future<Output = Result<T>>) -> Result<T> {
+    tokio::time::timeout(PING_TIMEOUT, fut).await?
+}
+
+impl ServerStatus {
+    fn resolved(mut self, host: &str, port: u16) -> Self {
+        self.resolved_host = host.to_string();
+        self.resolved_port = port;
+        self
+    }
+}
+
+// Vanilla closes the connection after one Pong, so extra samples each open their own
+async fn sample_latency(host: &str, port: u16, options: PingOptions, first_rtt: u128) -> LatencyStats {
+    let mut rtts = vec![first_rtt];
+    let deadline = tokio::time::Instant::now() + SAMPLING_DEADLINE;
+    for _ in 1..options.samples {
+        let rtt = tokio::time::timeout_at(deadline, async {
+            let mut stream = connect(host, port).await?;
+            send_handshake(&mut stream, host, port, options.protocol_version).await?;
+            ping_pong(&mut stream).await
+        })
+        .await;
+        match rtt {
+            Ok(Ok(rtt)) => rtts.push(rtt),
+            Ok(Err(_)) => {}
+            Err(_) => break,
+        }
+    }
+
+    LatencyStats {
+        min: rtts.iter().copied().min().unwrap_or(first_rtt),
+        avg: rtts.iter().sum::<u128>() / rtts.len() as u128,
+        max: rtts.iter().copied().max().unwrap_or(first_rtt),
+        samples: rtts.len() as u32,
+    }
+}
+
+async fn send_handshake(stream: &mut TcpStream, host: &str, port: u16, protocol_version: i32) -> Result<()> {
     let mut handshake = Vec::new();
     write_var_int(&mut handshake, 0x00); // Packet ID
-    write_var_int(&mut handshake, 47); // Protocol version (1.8)
+    write_var_int(&mut handshake, protocol_version);
     write_string(&mut handshake, host);
     handshake.extend_from_slice(&port.to_be_bytes());
     write_var_int(&mut handshake, 1); // Next state: 1 (status)
 
-    write_packet(&mut stream, handshake).await?;
+    write_packet(stream, handshake).await
+}
+
+// Times a Ping (0x01) carrying a timestamp until the matching Pong comes back
+async fn ping_pong(stream: &mut TcpStream) -> Result<u128> {
+    let payload = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default();
+    let mut packet = Vec::new();
+    write_var_int(&mut packet, 0x01); // Packet ID
+    packet.extend_from_slice(&payload.to_be_bytes());
+
+    let start = std::time::Instant::now();
+    write_packet(stream, packet).await?;
+
+    let _packet_len = read_var_int(stream).await?;
+    let packet_id = read_var_int(stream).await?;
+    let mut echoed = [0; 8];
+    stream.read_exact(&mut echoed).await?;
+    let rtt = start.elapsed().as_millis();
+
+    if packet_id != 0x01 || i64::from_be_bytes(echoed) != payload {
+        return Err(anyhow::anyhow!("Invalid pong"));
+    }
+    Ok(rtt)
+}
+
+// Returns the status plus the Ping/Pong round trip, if the server answered one
+async fn ping_modern(mut stream: TcpStream, host: &str, port: u16, protocol_version: i32) -> Result<(ServerStatus, Option<u128>)> {
+    let status = timed(read_status(&mut stream, host, port, protocol_version)).await?;
+
+    // Some servers close right after the status response or never answer the Ping; keep the status either way
+    let rtt = tokio::time::timeout(PONG_TIMEOUT, ping_pong(&mut stream)).await.ok().and_then(|rtt| rtt.ok());
+    Ok((status, rtt))
+}
+
+async fn read_status(stream: &mut TcpStream, host: &str, port: u16, protocol_version: i32) -> Result<ServerStatus> {
+    let start = std::time::Instant::now();
+
+    // Handshake
+    send_handshake(stream, host, port, protocol_version).await?;
 
     // Request
     let request = vec![0x00]; // Packet ID
-    write_packet(&mut stream, request).await?;
+    write_packet(stream, request).await?;
 
     // Response
-    let packet_len = read_var_int(&mut stream).await?;
-    let packet_id = read_var_int(&mut stream).await?;
+    let packet_len = read_var_int(stream).await?;
+    let packet_id = read_var_int(stream).await?;
 
     if packet_id != 0x00 {
         return Err(anyhow::anyhow!("Invalid packet ID"));
     }
 
-    let json_len = read_var_int(&mut stream).await?;
+    let json_len = read_var_int(stream).await?;
     let mut json_buf = vec![0; json_len as usize];
     stream.read_exact(&mut json_buf).await?;
 
@@ -60,42 +455,256 @@ pub async fn ping_server(host: &str, port: u16) -> Result<ServerStatus> {
 
     let json_val: serde_json::Value = serde_json::from_str(&json_str)?;
     
-    let description = if let Some(d) = json_val.get("description") {
-        parse_description(d)
-    } else {
-        "No description".to_string()
+    let description_spans = match json_val.get("description") {
+        Some(d) => crate::motd::parse_component(d),
+        None => crate::motd::parse_component(&serde_json::json!("No description")),
     };
+    let description = crate::motd::plain_text(&description_spans);
     
     let players = serde_json::from_value(json_val.get("players").cloned().unwrap_or(serde_json::json!({"max":0,"online":0})))?;
     let version = serde_json::from_value(json_val.get("version").cloned().unwrap_or(serde_json::json!({"name":"?","protocol":0})))?;
     let favicon = json_val.get("favicon").and_then(|f| f.as_str()).map(|s| s.to_string());
+    let mod_info = parse_mod_info(&json_val);
+    let enforces_secure_chat = json_val.get("enforcesSecureChat").and_then(|v| v.as_bool());
+    let previews_chat = json_val.get("previewsChat").and_then(|v| v.as_bool());
 
     Ok(ServerStatus {
         description,
+        description_spans,
         players,
         version,
         favicon,
         latency,
+        resolved_host: String::new(),
+        resolved_port: 0,
+        ping_protocol: PingProtocol::Modern,
+        latency_stats: None,
+        mod_info,
+        enforces_secure_chat,
+        previews_chat,
     })
 }
 
-fn parse_description(v: &serde_json::Value) -> String {
-    if let Some(s) = v.as_str() {
-        return s.to_string();
+async fn ping_legacy(mut stream: TcpStream, host: &str, port: u16, protocol: PingProtocol) -> Result<ServerStatus> {
+    let start = std::time::Instant::now();
+
+    let mut request = vec![0xFE];
+    match protocol {
+        PingProtocol::Legacy16 => {
+            request.push(0x01);
+            // Plugin message carrying MC|PingHost, as the 1.6 client sends it
+            request.push(0xFA);
+            write_legacy_string(&mut request, "MC|PingHost");
+            let host_utf16: Vec<u16> = host.encode_utf16().collect();
+            request.extend_from_slice(&(7 + 2 * host_utf16.len() as u16).to_be_bytes());
+            request.push(LEGACY_PROTOCOL_VERSION);
+            write_legacy_string(&mut request, host);
+            request.extend_from_slice(&(port as i32).to_be_bytes());
+        }
+        PingProtocol::Legacy14 => request.push(0x01),
+        _ => {}
+    }
+    stream.write_all(&request).await?;
+
+    // The reply is a kick packet: 0xFF, a length in UTF-16 units, then the UTF-16BE string
+    let mut header = [0; 3];
+    stream.read_exact(&mut header).await?;
+    if header[0] != 0xFF {
+        return Err(anyhow::anyhow!("Invalid legacy ping response"));
     }
-    if let Some(obj) = v.as_object() {
-        let mut result = String::new();
-        if let Some(text) = obj.get("text").and_then(|t| t.as_str()) {
-            result.push_str(text);
+    let len = u16::from_be_bytes([header[1], header[2]]) as usize;
+    let mut buf = vec![0; len * 2];
+    stream.read_exact(&mut buf).await?;
+    let latency = start.elapsed().as_millis();
+
+    let units: Vec<u16> = buf.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+    let payload = String::from_utf16(&units)?;
+
+    // 1.4+ servers answer "§1\0protocol\0version\0motd\0online\0max", older ones "motd§online§max"
+    let (description, online, max, version) = if let Some(rest) = payload.strip_prefix("\u{a7}1\0") {
+        let fields: Vec<&str> = rest.split('\0').collect();
+        if fields.len() < 5 {
+            return Err(anyhow::anyhow!("Malformed legacy ping response"));
         }
-        if let Some(extra) = obj.get("extra").and_then(|e| e.as_array()) {
-            for item in extra {
-                result.push_str(&parse_description(item));
+        let version = Version {
+            name: fields[1].to_string(),
+            protocol: fields[0].parse().unwrap_or(-1),
+        };
+        (fields[2].to_string(), fields[3], fields[4], version)
+    } else {
+        let mut fields = payload.rsplitn(3, '\u{a7}');
+        let (Some(max), Some(online), Some(motd)) = (fields.next(), fields.next(), fields.next()) else {
+            return Err(anyhow::anyhow!("Malformed legacy ping response"));
+        };
+        let version = Version {
+            name: "Beta 1.8 - 1.3".to_string(),
+            protocol: -1,
+        };
+        (motd.to_string(), online, max, version)
+    };
+
+    let description_spans = crate::motd::parse_component(&serde_json::Value::String(description));
+    Ok(ServerStatus {
+        description: crate::motd::plain_text(&description_spans),
+        description_spans,
+        players: Players {
+            max: max.parse().unwrap_or(0),
+            online: online.parse().unwrap_or(0),
+            sample: Vec::new(),
+        },
+        version,
+        favicon: None,
+        latency,
+        resolved_host: String::new(),
+        resolved_port: 0,
+        ping_protocol: protocol,
+        latency_stats: None,
+        mod_info: None,
+        enforces_secure_chat: None,
+        previews_chat: None,
+    })
+}
+
+fn write_legacy_string(buf: &mut Vec<u8>, s: &str) {
+    let units: Vec<u16> = s.encode_utf16().collect();
+    buf.extend_from_slice(&(units.len() as u16).to_be_bytes());
+    for unit in units {
+        buf.extend_from_slice(&unit.to_be_bytes());
+    }
+}
+
+fn parse_mod_info(status: &serde_json::Value) -> Option<ModInfo> {
+    if let Some(modinfo) = status.get("modinfo") {
+        let mods = modinfo["modList"]
+            .as_array()
+            .map(|list| {
+                list.iter()
+                    .filter_map(|m| {
+                        Some(ModEntry {
+                            id: m["modid"].as_str()?.to_string(),
+                            version: m["version"].as_str().map(|v| v.to_string()),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        return Some(ModInfo {
+            loader: modinfo["type"].as_str().unwrap_or("FML").to_string(),
+            mods,
+            fml_network_version: None,
+            truncated: false,
+        });
+    }
+
+    let forge_data = status.get("forgeData")?;
+    let fml_network_version = forge_data["fmlNetworkVersion"].as_i64().map(|v| v as i32);
+    // 1.18+ packs the mod list into the binary "d" string instead of "mods"
+    if let Some((truncated, mods)) = forge_data["d"].as_str().and_then(decode_forge_data) {
+        return Some(ModInfo {
+            loader: "FORGE".to_string(),
+            mods,
+            fml_network_version,
+            truncated,
+        });
+    }
+    let mods = forge_data["mods"]
+        .as_array()
+        .map(|list| {
+            list.iter()
+                .filter_map(|m| {
+                    Some(ModEntry {
+                        id: m["modId"].as_str()?.to_string(),
+                        version: m["modmarker"].as_str().map(|v| v.to_string()),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(ModInfo {
+        loader: "FORGE".to_string(),
+        mods,
+        fml_network_version,
+        truncated: forge_data["truncated"].as_bool().unwrap_or(false),
+    })
+}
+
+// Reverses Forge's ServerStatusPing.encodeOptimized: two chars of byte length, then 15 bits per char
+fn decode_forge_data(encoded: &str) -> Option<(bool, Vec<ModEntry>)> {
+    let chars: Vec<u32> = encoded.encode_utf16().map(|c| c as u32 & 0x7FFF).collect();
+    if chars.len() < 2 {
+        return None;
+    }
+    let size = (chars[0] | (chars[1] << 15)) as usize;
+
+    let mut bytes = Vec::with_capacity(size);
+    let mut buffer: u32 = 0;
+    let mut bits: u32 = 0;
+    for c in &chars[2..] {
+        while bits >= 8 {
+            bytes.push(buffer as u8);
+            buffer >>= 8;
+            bits -= 8;
+        }
+        buffer |= c << bits;
+        bits += 15;
+    }
+    while bytes.len() < size && bits > 0 {
+        bytes.push(buffer as u8);
+        buffer >>= 8;
+        bits = bits.saturating_sub(8);
+    }
+    bytes.truncate(size);
+
+    let mut reader = ByteReader { data: &bytes, pos: 0 };
+    let truncated = reader.u8()? != 0;
+    let mod_count = u16::from_be_bytes([reader.u8()?, reader.u8()?]);
+    let mut mods = Vec::with_capacity(mod_count as usize);
+    for _ in 0..mod_count {
+        let flags = reader.var_int()?;
+        let server_only = flags & 1 != 0;
+        let id = reader.string()?;
+        let version = if server_only { None } else { Some(reader.string()?) };
+        // Channel path, version and required flag; not needed for the mod list
+        for _ in 0..(flags >> 1) {
+            reader.string()?;
+            reader.string()?;
+            reader.u8()?;
+        }
+        mods.push(ModEntry { id, version });
+    }
+    Some((truncated, mods))
+}
+
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl ByteReader<'_> {
+    fn u8(&mut self) -> Option<u8> {
+        let b = *self.data.get(self.pos)?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    fn var_int(&mut self) -> Option<i32> {
+        let mut result = 0;
+        for i in 0..5 {
+            let b = self.u8()?;
+            result |= ((b & 0x7F) as i32) << (7 * i);
+            if b & 0x80 == 0 {
+                return Some(result);
             }
         }
-        return result;
+        None
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = self.var_int()? as usize;
+        let bytes = self.data.get(self.pos..self.pos + len)?;
+        self.pos += len;
+        String::from_utf8(bytes.to_vec()).ok()
     }
-    String::new()
 }
 
 async fn write_packet(stream: &mut TcpStream, data: Vec<u8>) -> Result<()> {
@@ -146,3 +755,39 @@ async fn read_var_int(stream: &mut TcpStream) -> Result<i32> {
     }
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Answers every SRV lookup with the same record, or with none
+    struct FakeResolver(Option<(String, u16)>);
+
+    #[async_trait]
+    impl SrvResolver for FakeResolver {
+        async fn resolve_srv(&self, _host: &str) -> Result<Option<(String, u16)>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[tokio::test]
+    async fn resolve_address_uses_srv_target() {
+        let resolver = FakeResolver(Some(("mc.example.net".to_string(), 25570)));
+        let address = resolve_address(&resolver, "example.net", None).await;
+        assert_eq!(address, ("mc.example.net".to_string(), 25570));
+    }
+
+    #[tokio::test]
+    async fn resolve_address_falls_back_without_srv_record() {
+        let resolver = FakeResolver(None);
+        let address = resolve_address(&resolver, "example.net", None).await;
+        assert_eq!(address, ("example.net".to_string(), DEFAULT_PORT));
+    }
+
+    #[tokio::test]
+    async fn resolve_address_skips_srv_for_explicit_port() {
+        let resolver = FakeResolver(Some(("mc.example.net".to_string(), 25570)));
+        let address = resolve_address(&resolver, "example.net", Some(25566)).await;
+        assert_eq!(address, ("example.net".to_string(), 25566));
+    }
+}
@@ -0,0 +1,206 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+const MAGIC: [u8; 2] = [0xFE, 0xFD];
+const TYPE_HANDSHAKE: u8 = 9;
+const TYPE_STAT: u8 = 0;
+const QUERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+// GameSpy4 query results; the full-stat-only fields stay empty for a basic stat
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryStatus {
+    pub motd: String,
+    pub game_type: String,
+    pub map: String,
+    pub num_players: i32,
+    pub max_players: i32,
+    pub host_port: u16,
+    pub host_ip: String,
+    pub full: bool,
+    pub version: Option<String>,
+    pub server_software: Option<String>,
+    pub plugins: Vec<String>,
+    pub players: Vec<String>,
+    // Every key/value pair of the full stat, including ones not mapped above
+    pub raw: HashMap<String, String>,
+}
+
+struct QueryClient {
+    socket: UdpSocket,
+    session_id: i32,
+}
+
+impl QueryClient {
+    async fn connect(host: &str, port: u16) -> Result<Self> {
+        let socket = UdpSocket::bind(("0.0.0.0", 0)).await?;
+        socket.connect((host, port)).await?;
+        // Only the low 4 bits of each byte are echoed back reliably
+        let session_id = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos() as i32)
+            .unwrap_or(1)
+            & 0x0F0F0F0F;
+        Ok(Self { socket, session_id })
+    }
+
+    async fn request(&self, packet_type: u8, payload: &[u8]) -> Result<Vec<u8>> {
+        let mut packet = MAGIC.to_vec();
+        packet.push(packet_type);
+        packet.extend_from_slice(&self.session_id.to_be_bytes());
+        packet.extend_from_slice(payload);
+        self.socket.send(&packet).await?;
+
+        let mut buf = vec![0; 65535];
+        let len = tokio::time::timeout(QUERY_TIMEOUT, self.socket.recv(&mut buf)).await??;
+        buf.truncate(len);
+        if buf.len() < 5 || buf[0] != packet_type || buf[1..5] != self.session_id.to_be_bytes() {
+            return Err(anyhow::anyhow!("Unexpected query response"));
+        }
+        Ok(buf.split_off(5))
+    }
+
+    // The challenge token comes back as a NUL-terminated decimal string
+    async fn handshake(&self) -> Result<i32> {
+        let response = self.request(TYPE_HANDSHAKE, &[]).await?;
+        let mut reader = Reader::new(&response);
+        let token = reader.string().ok_or_else(|| anyhow::anyhow!("Missing challenge token"))?;
+        Ok(token.trim().parse()?)
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    // Query strings are ISO-8859-1
+    fn string(&mut self) -> Option<String> {
+        let rest = self.data.get(self.pos..)?;
+        let end = rest.iter().position(|b| *b == 0)?;
+        self.pos += end + 1;
+        Some(rest[..end].iter().map(|b| *b as char).collect())
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        (self.pos + len <= self.data.len()).then(|| self.pos += len)
+    }
+
+    fn u16_le(&mut self) -> Option<u16> {
+        let bytes = self.data.get(self.pos..self.pos + 2)?;
+        self.pos += 2;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+}
+
+// "CraftBukkit on Bukkit 1.20.4: WorldEdit 7.2; Essentials 2.20" -> software plus plugin list
+fn parse_plugins(value: &str) -> (Option<String>, Vec<String>) {
+    if value.trim().is_empty() {
+        return (None, Vec::new());
+    }
+    match value.split_once(':') {
+        Some((software, plugins)) => (
+            Some(software.trim().to_string()),
+            plugins.split(';').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect(),
+        ),
+        None => (Some(value.trim().to_string()), Vec::new()),
+    }
+}
+
+async fn basic_stat(client: &QueryClient, token: i32) -> Result<QueryStatus> {
+    let response = client.request(TYPE_STAT, &token.to_be_bytes()).await?;
+    let mut reader = Reader::new(&response);
+    let malformed = || anyhow::anyhow!("Malformed basic stat response");
+
+    let motd = reader.string().ok_or_else(malformed)?;
+    let game_type = reader.string().ok_or_else(malformed)?;
+    let map = reader.string().ok_or_else(malformed)?;
+    let num_players = reader.string().ok_or_else(malformed)?;
+    let max_players = reader.string().ok_or_else(malformed)?;
+    let host_port = reader.u16_le().ok_or_else(malformed)?;
+    let host_ip = reader.string().ok_or_else(malformed)?;
+
+    Ok(QueryStatus {
+        motd,
+        game_type,
+        map,
+        num_players: num_players.trim().parse().unwrap_or(0),
+        max_players: max_players.trim().parse().unwrap_or(0),
+        host_port,
+        host_ip,
+        full: false,
+        version: None,
+        server_software: None,
+        plugins: Vec::new(),
+        players: Vec::new(),
+        raw: HashMap::new(),
+    })
+}
+
+async fn full_stat(client: &QueryClient, token: i32) -> Result<QueryStatus> {
+    let mut payload = token.to_be_bytes().to_vec();
+    payload.extend_from_slice(&[0, 0, 0, 0]);
+    let response = client.request(TYPE_STAT, &payload).await?;
+    let mut reader = Reader::new(&response);
+    let malformed = || anyhow::anyhow!("Malformed full stat response");
+
+    // "splitnum\0\x80\0" padding, then key/value pairs up to an empty key
+    reader.skip(11).ok_or_else(malformed)?;
+    let mut raw = HashMap::new();
+    loop {
+        let key = reader.string().ok_or_else(malformed)?;
+        if key.is_empty() {
+            break;
+        }
+        let value = reader.string().ok_or_else(malformed)?;
+        raw.insert(key, value);
+    }
+
+    // "\x01player_\0\0" padding, then player names up to an empty one
+    reader.skip(10).ok_or_else(malformed)?;
+    let mut players = Vec::new();
+    while let Some(name) = reader.string() {
+        if name.is_empty() {
+            break;
+        }
+        players.push(name);
+    }
+
+    let field = |key: &str| raw.get(key).cloned().unwrap_or_default();
+    let (server_software, plugins) = parse_plugins(&field("plugins"));
+    Ok(QueryStatus {
+        motd: field("hostname"),
+        game_type: field("gametype"),
+        map: field("map"),
+        num_players: field("numplayers").trim().parse().unwrap_or(0),
+        max_players: field("maxplayers").trim().parse().unwrap_or(0),
+        host_port: field("hostport").trim().parse().unwrap_or(0),
+        host_ip: field("hostip"),
+        full: true,
+        version: raw.get("version").cloned(),
+        server_software,
+        plugins,
+        players,
+        raw,
+    })
+}
+
+// Queries the server's `query.port`, which defaults to the game port
+pub async fn query_server(host: &str, port: Option<u16>, full: bool) -> Result<QueryStatus> {
+    let resolver = crate::server_ping::configured_resolver();
+    let (host, port) = crate::server_ping::resolve_address(&resolver, host, port).await;
+    let client = QueryClient::connect(&host, port).await?;
+    let token = client.handshake().await?;
+    if full {
+        full_stat(&client, token).await
+    } else {
+        basic_stat(&client, token).await
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use crate::nbt::Tag;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerEntry {
+    pub name: String,
+    pub ip: String,
+    // Base64 PNG the game caches from the last status ping
+    pub icon: Option<String>,
+    // None means the game will ask before using the server resource pack
+    pub accept_textures: Option<bool>,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+impl ServerEntry {
+    fn from_tag(tag: &Tag) -> Self {
+        Self {
+            name: tag.get("name").and_then(|t| t.as_str()).unwrap_or_default().to_string(),
+            ip: tag.get("ip").and_then(|t| t.as_str()).unwrap_or_default().to_string(),
+            icon: tag.get("icon").and_then(|t| t.as_str()).map(|s| s.to_string()),
+            accept_textures: tag.get("acceptTextures").and_then(|t| t.as_byte()).map(|b| b != 0),
+            hidden: tag.get("hidden").and_then(|t| t.as_byte()).map(|b| b != 0).unwrap_or(false),
+        }
+    }
+
+    // Updates the fields we know about, leaving anything else the game stored untouched
+    fn apply_to(&self, tag: &mut Tag) {
+        tag.set("name", Tag::String(self.name.clone()));
+        tag.set("ip", Tag::String(self.ip.clone()));
+        match &self.icon {
+            Some(icon) => tag.set("icon", Tag::String(icon.clone())),
+            None => tag.remove("icon"),
+        }
+        match self.accept_textures {
+            Some(accept) => tag.set("acceptTextures", Tag::Byte(accept as i8)),
+            None => tag.remove("acceptTextures"),
+        }
+        if self.hidden {
+            tag.set("hidden", Tag::Byte(1));
+        } else {
+            tag.remove("hidden");
+        }
+    }
+}
+
+// servers.dat lives in the game directory, which is the version folder for isolated instances
+fn servers_dat_path(app: &AppHandle, version_id: &str, game_path: Option<String>) -> Result<PathBuf, String> {
+    let details = crate::launcher::get_version_details(app.clone(), version_id.to_string(), game_path.clone())?;
+    let config = crate::config::load_config();
+    let isolated = crate::config::should_isolate(&config.isolation_mode, details.is_modded, &details.version_type);
+    let working_dir = crate::version_path::get_game_working_dir(app, version_id, game_path, isolated)?;
+    Ok(working_dir.join("servers.dat"))
+}
+
+fn read_root(path: &Path) -> Result<(String, Tag), String> {
+    if !path.exists() {
+        return Ok((String::new(), Tag::Compound(Vec::new())));
+    }
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    crate::nbt::read(&data)
+}
+
+fn read_server_tags(path: &Path) -> Result<Vec<Tag>, String> {
+    let (_, root) = read_root(path)?;
+    match root.get("servers") {
+        Some(Tag::List(_, items)) => Ok(items.clone()),
+        _ => Ok(Vec::new()),
+    }
+}
+
+// Only the server list is replaced; anything else the game or other tools keep in the root survives
+fn write_server_tags(path: &Path, servers: Vec<Tag>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let (name, mut root) = read_root(path)?;
+    if !matches!(root, Tag::Compound(_)) {
+        root = Tag::Compound(Vec::new());
+    }
+    root.set("servers", Tag::List(10, servers));
+    // Write next to the target first so the game never sees a half-written file
+    let temp_path = path.with_extension("dat.tmp");
+    std::fs::write(&temp_path, crate::nbt::write(&name, &root)).map_err(|e| e.to_string())?;
+    std::fs::rename(&temp_path, path).map_err(|e| e.to_string())
+}
+
+fn to_entries(tags: &[Tag]) -> Vec<ServerEntry> {
+    tags.iter().map(ServerEntry::from_tag).collect()
+}
+
+// Adds the entry, or updates the existing one with the same address
+fn upsert_server(path: &Path, entry: &ServerEntry) -> Result<Vec<ServerEntry>, String> {
+    let mut tags = read_server_tags(path)?;
+    let existing = tags
+        .iter_mut()
+        .find(|t| t.get("ip").and_then(|ip| ip.as_str()).is_some_and(|ip| ip.eq_ignore_ascii_case(&entry.ip)));
+    match existing {
+        Some(tag) => entry.apply_to(tag),
+        None => {
+            let mut tag = Tag::Compound(Vec::new());
+            entry.apply_to(&mut tag);
+            tags.push(tag);
+        }
+    }
+    write_server_tags(path, tags.clone())?;
+    Ok(to_entries(&tags))
+}
+
+// Puts our server in the multiplayer list of a freshly provisioned client
+pub fn provision_server_entry(app: &AppHandle, version_id: &str, game_path: Option<String>, name: &str, address: &str) -> Result<(), String> {
+    let path = servers_dat_path(app, version_id, game_path)?;
+    let entry = ServerEntry {
+        name: name.to_string(),
+        ip: address.to_string(),
+        icon: None,
+        accept_textures: Some(true),
+        hidden: false,
+    };
+    upsert_server(&path, &entry).map(|_| ())
+}
+
+#[tauri::command]
+pub fn list_server_entries(app: AppHandle, version_id: String, game_path: Option<String>) -> Result<Vec<ServerEntry>, String> {
+    let path = servers_dat_path(&app, &version_id, game_path)?;
+    Ok(to_entries(&read_server_tags(&path)?))
+}
+
+#[tauri::command]
+pub fn add_server_entry(app: AppHandle, version_id: String, entry: ServerEntry, game_path: Option<String>) -> Result<Vec<ServerEntry>, String> {
+    if entry.ip.trim().is_empty() {
+        return Err("Server address is required".to_string());
+    }
+    let path = servers_dat_path(&app, &version_id, game_path)?;
+    upsert_server(&path, &entry)
+}
+
+#[tauri::command]
+pub fn remove_server_entry(app: AppHandle, version_id: String, index: usize, game_path: Option<String>) -> Result<Vec<ServerEntry>, String> {
+    let path = servers_dat_path(&app, &version_id, game_path)?;
+    let mut tags = read_server_tags(&path)?;
+    if index >= tags.len() {
+        return Err(format!("No server entry at index {}", index));
+    }
+    tags.remove(index);
+    write_server_tags(&path, tags.clone())?;
+    Ok(to_entries(&tags))
+}
+
+#[tauri::command]
+pub fn move_server_entry(app: AppHandle, version_id: String, from: usize, to: usize, game_path: Option<String>) -> Result<Vec<ServerEntry>, String> {
+    let path = servers_dat_path(&app, &version_id, game_path)?;
+    let mut tags = read_server_tags(&path)?;
+    if from >= tags.len() || to >= tags.len() {
+        return Err(format!("Server entry index out of range ({} entries)", tags.len()));
+    }
+    let tag = tags.remove(from);
+    tags.insert(to, tag);
+    write_server_tags(&path, tags.clone())?;
+    Ok(to_entries(&tags))
+}
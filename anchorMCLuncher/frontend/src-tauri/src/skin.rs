@@ -0,0 +1,257 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State};
+
+const MOJANG_SESSION_SERVER: &str = "https://sessionserver.mojang.com";
+const MINECRAFT_SERVICES_API: &str = "https://api.minecraftservices.com";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum SkinModel {
+    #[serde(rename = "classic")]
+    Classic,
+    #[serde(rename = "slim")]
+    Slim,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextureInfo {
+    pub url: String,
+    pub local_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileTextures {
+    pub id: String,
+    pub name: String,
+    pub model: SkinModel,
+    pub skin: Option<TextureInfo>,
+    pub cape: Option<TextureInfo>,
+    // True when the session server was unreachable and this is the last known state
+    pub offline: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct SessionProfile {
+    id: String,
+    name: String,
+    #[serde(default)]
+    properties: Vec<ProfileProperty>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProfileProperty {
+    name: String,
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TexturesPayload {
+    textures: std::collections::HashMap<String, TexturePayload>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TexturePayload {
+    url: String,
+    metadata: Option<std::collections::HashMap<String, String>>,
+}
+
+fn texture_cache_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app.path().app_data_dir().map_err(|e| e.to_string())?.join("textures"))
+}
+
+fn profile_cache_path(cache_dir: &Path, uuid: &str) -> PathBuf {
+    cache_dir.join("profiles").join(format!("{}.json", uuid))
+}
+
+// Both Mojang and authlib-injector servers expect the undashed form
+fn normalize_uuid(uuid: &str) -> String {
+    uuid.replace('-', "").to_lowercase()
+}
+
+// Yggdrasil servers are addressed through their (ALI-resolved) API root, None means Mojang/Microsoft
+async fn resolve_api_root(state: &crate::auth_server::AuthServerState, auth_server: &Option<String>) -> Option<String> {
+    let server = auth_server.as_ref().filter(|s| !s.trim().is_empty())?;
+    match crate::auth_server::get_metadata(state, server, false).await {
+        Ok(metadata) => Some(metadata.info.api_root),
+        Err(_) => Some(server.trim_end_matches('/').to_string()),
+    }
+}
+
+fn decode_textures(profile: &SessionProfile) -> Result<(SkinModel, Option<String>, Option<String>), String> {
+    let Some(property) = profile.properties.iter().find(|p| p.name == "textures") else {
+        return Ok((SkinModel::Classic, None, None));
+    };
+    let decoded = base64::engine::general_purpose::STANDARD
+        .decode(property.value.as_bytes())
+        .map_err(|e| format!("Invalid textures property: {}", e))?;
+    let payload: TexturesPayload = serde_json::from_slice(&decoded).map_err(|e| e.to_string())?;
+
+    let skin = payload.textures.get("SKIN");
+    let model = match skin.and_then(|s| s.metadata.as_ref()).and_then(|m| m.get("model")) {
+        Some(m) if m == "slim" => SkinModel::Slim,
+        _ => SkinModel::Classic,
+    };
+
+    Ok((
+        model,
+        skin.map(|s| s.url.clone()),
+        payload.textures.get("CAPE").map(|c| c.url.clone()),
+    ))
+}
+
+// The URL comes from a remote profile, so the cache key is its hash rather than anything in it
+async fn cache_texture(client: &reqwest::Client, cache_dir: &Path, url: &str) -> Option<String> {
+    let path = cache_dir.join(format!("{:x}.png", Sha1::digest(url.as_bytes())));
+    if !path.exists() {
+        let resp = client.get(url).send().await.ok()?;
+        if !resp.status().is_success() {
+            return None;
+        }
+        let bytes = resp.bytes().await.ok()?;
+        std::fs::create_dir_all(cache_dir).ok()?;
+        std::fs::write(&path, bytes).ok()?;
+    }
+    Some(path.to_string_lossy().to_string())
+}
+
+async fn fetch_profile(client: &reqwest::Client, session_root: &str, uuid: &str) -> Result<SessionProfile, String> {
+    let url = format!("{}/session/minecraft/profile/{}?unsigned=false", session_root, uuid);
+    let resp = client.get(&url).send().await.map_err(|e| e.to_string())?;
+    if resp.status() == reqwest::StatusCode::NO_CONTENT || resp.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(format!("Profile {} not found", uuid));
+    }
+    if !resp.status().is_success() {
+        return Err(format!("Session server error: {}", resp.status()));
+    }
+    resp.json().await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_profile_textures(
+    app: AppHandle,
+    state: State<'_, crate::auth_server::AuthServerState>,
+    uuid: String,
+    auth_server: Option<String>
+) -> Result<ProfileTextures, String> {
+    let uuid = normalize_uuid(&uuid);
+    let cache_dir = texture_cache_dir(&app)?;
+    let cache_path = profile_cache_path(&cache_dir, &uuid);
+    let session_root = match resolve_api_root(&state, &auth_server).await {
+        Some(root) => format!("{}/sessionserver", root),
+        None => MOJANG_SESSION_SERVER.to_string(),
+    };
+
+    let client = reqwest::Client::new();
+    let profile = match fetch_profile(&client, &session_root, &uuid).await {
+        Ok(profile) => profile,
+        Err(e) => {
+            // Fall back to the last profile we saw so avatars still render offline
+            if let Ok(content) = std::fs::read_to_string(&cache_path) {
+                if let Ok(mut cached) = serde_json::from_str::<ProfileTextures>(&content) {
+                    cached.offline = true;
+                    return Ok(cached);
+                }
+            }
+            return Err(e);
+        }
+    };
+
+    let (model, skin_url, cape_url) = decode_textures(&profile)?;
+    let mut skin = None;
+    if let Some(url) = skin_url {
+        let local_path = cache_texture(&client, &cache_dir, &url).await;
+        skin = Some(TextureInfo { url, local_path });
+    }
+    let mut cape = None;
+    if let Some(url) = cape_url {
+        let local_path = cache_texture(&client, &cache_dir, &url).await;
+        cape = Some(TextureInfo { url, local_path });
+    }
+
+    let textures = ProfileTextures {
+        id: profile.id,
+        name: profile.name,
+        model,
+        skin,
+        cape,
+        offline: false,
+    };
+
+    if let Some(parent) = cache_path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+    if let Ok(content) = serde_json::to_string_pretty(&textures) {
+        let _ = std::fs::write(&cache_path, content);
+    }
+
+    Ok(textures)
+}
+
+#[tauri::command]
+pub async fn upload_skin(
+    state: State<'_, crate::auth_server::AuthServerState>,
+    uuid: String,
+    access_token: String,
+    file_path: String,
+    model: SkinModel,
+    auth_server: Option<String>
+) -> Result<(), String> {
+    let uuid = normalize_uuid(&uuid);
+    let bytes = std::fs::read(&file_path).map_err(|e| e.to_string())?;
+    let part = reqwest::multipart::Part::bytes(bytes)
+        .file_name("skin.png")
+        .mime_str("image/png")
+        .map_err(|e| e.to_string())?;
+
+    let client = reqwest::Client::new();
+    let request = match resolve_api_root(&state, &auth_server).await {
+        Some(root) => {
+            // authlib-injector texture upload API: empty model means classic
+            let model_field = if model == SkinModel::Slim { "slim" } else { "" };
+            let form = reqwest::multipart::Form::new()
+                .text("model", model_field)
+                .part("file", part);
+            client.put(format!("{}/api/user/profile/{}/skin", root, uuid)).multipart(form)
+        }
+        None => {
+            let variant = if model == SkinModel::Slim { "slim" } else { "classic" };
+            let form = reqwest::multipart::Form::new()
+                .text("variant", variant)
+                .part("file", part);
+            client.post(format!("{}/minecraft/profile/skins", MINECRAFT_SERVICES_API)).multipart(form)
+        }
+    };
+
+    let resp = request.bearer_auth(&access_token).send().await.map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let detail = resp.text().await.unwrap_or_default();
+        return Err(format!("Skin upload failed (Status {}): {}", status, detail));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn reset_skin(
+    state: State<'_, crate::auth_server::AuthServerState>,
+    uuid: String,
+    access_token: String,
+    auth_server: Option<String>
+) -> Result<(), String> {
+    let uuid = normalize_uuid(&uuid);
+    let client = reqwest::Client::new();
+    let url = match resolve_api_root(&state, &auth_server).await {
+        Some(root) => format!("{}/api/user/profile/{}/skin", root, uuid),
+        None => format!("{}/minecraft/profile/skins/active", MINECRAFT_SERVICES_API),
+    };
+
+    let resp = client.delete(&url).bearer_auth(&access_token).send().await.map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        let status = resp.status();
+        let detail = resp.text().await.unwrap_or_default();
+        return Err(format!("Skin reset failed (Status {}): {}", status, detail));
+    }
+    Ok(())
+}
@@ -3,7 +3,7 @@ import { openUrl } from "@tauri-apps/plugin-opener";
 import { Window } from "@tauri-apps/api/window";
 import { WebviewWindow } from "@tauri-apps/api/webviewWindow";
 import styled from "styled-components";
-import { User, Server, DockerServer, ServerStatus } from "./types";
+import { User, Server, DockerServer, ServerStatus, AuthServerInfo } from "./types";
 import { SettingsModal } from "./components/SettingsModal";
 import { MessageModal } from "./components/MessageModal";
 import { LoginCard } from "./components/LoginCard";
@@ -288,6 +288,14 @@ function App() {
   useEffect(() => {
     api.setApiBaseUrl(authServer);
   }, [authServer]);
+
+  const [authServerName, setAuthServerName] = useState<string | null>(null);
+  useEffect(() => {
+    setAuthServerName(null);
+    invoke<AuthServerInfo>('get_auth_server_metadata', { authServer })
+      .then(info => setAuthServerName(info.server_name || null))
+      .catch(e => console.warn("Failed to fetch auth server metadata:", e));
+  }, [authServer]);
   const [isLoading, setIsLoading] = useState(false);
   const [showSettings, setShowSettings] = useState(false);
   const [servers, setServers] = useState<Server[]>([]);
@@ -381,12 +389,10 @@ function App() {
       const status = await invoke<ServerStatus>('ping_server', { host: server.ip_address, port: server.port });
       setServers(prev => prev.map(s => {
         if (s.id === server.id) {
-          const description = status.description;
-          // Remove color codes if present (simple regex)
-          const cleanDescription = description.replace(/§[0-9a-fk-or]/g, '');
           return {
             ...s,
-            motd: cleanDescription,
+            motd: status.description,
+            motd_spans: status.description_spans,
             players: `${status.players.online}/${status.players.max}`,
             ping: status.latency,
             favicon: status.favicon
@@ -398,7 +404,7 @@ function App() {
       console.error(`Failed to ping ${server.name}:`, e);
       setServers(prev => prev.map(s => {
         if (s.id === server.id) {
-          return { ...s, ping: undefined, motd: "Offline" };
+          return { ...s, ping: undefined, motd: "Offline", motd_spans: undefined };
         }
         return s;
       }));
@@ -436,7 +442,10 @@ function App() {
 
     try {
        const serverId = ('id' in server && typeof server.id === 'number') ? server.id : '';
-       const url = `/index.html?window=download&versionId=${encodeURIComponent(server.name)}&containerId=${containerId}&serverId=${serverId}`;
+       const serverAddress = ('ip_address' in server && server.ip_address)
+           ? (server.port && server.port !== 25565 ? `${server.ip_address}:${server.port}` : server.ip_address)
+           : '';
+       const url = `/index.html?window=download&versionId=${encodeURIComponent(server.name)}&containerId=${containerId}&serverId=${serverId}&serverAddress=${encodeURIComponent(serverAddress)}`;
        
        // Open new window for download
        new WebviewWindow('download-' + Date.now(), {
@@ -752,6 +761,7 @@ function App() {
         onLogin={handleLogin}
         onRegister={handleRegister}
         onCAFLogin={handleCAFLogin}
+        serverName={authServerName}
       />
 
       <StatusArea>
@@ -1,6 +1,6 @@
 import React, { useState, useEffect } from 'react';
 import styled from 'styled-components';
-import { DockerServer, ServerStatus } from '../types';
+import { DockerServer, ServerStatus, TextSpan } from '../types';
 import { invoke } from '@tauri-apps/api/core';
 import { PlayIcon, StopIcon, CommandLineIcon, ArrowDownTrayIcon } from '@heroicons/react/24/solid';
 
@@ -73,6 +73,13 @@ const Motd = styled.div`
   margin-bottom: 2px;
 `;
 
+const spanStyle = (span: TextSpan): React.CSSProperties => ({
+  color: span.color,
+  fontWeight: span.bold ? 'bold' : undefined,
+  fontStyle: span.italic ? 'italic' : undefined,
+  textDecoration: [span.underlined && 'underline', span.strikethrough && 'line-through'].filter(Boolean).join(' ') || undefined,
+});
+
 const PlayerCount = styled.div`
   color: #64748b;
   font-size: 0.8rem;
@@ -175,8 +182,10 @@ export const DockerServerCard: React.FC<DockerServerCardProps> = ({ server, onSt
       <DetailInfo>
         {status ? (
             <>
-                <Motd title={status.description.replace(/§[0-9a-fk-or]/g, '')}>
-                    {status.description.replace(/§[0-9a-fk-or]/g, '')}
+                <Motd title={status.description}>
+                    {status.description_spans.map((span, i) => (
+                        <span key={i} style={spanStyle(span)}>{span.text}</span>
+                    ))}
                 </Motd>
                 <PlayerCount>
                     在线: {status.players.online}/{status.players.max}
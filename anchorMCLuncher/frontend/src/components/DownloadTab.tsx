@@ -252,6 +252,10 @@ export interface DownloadProgress {
   current_file_progress?: number | null;
   current_file_downloaded?: number | null;
   current_file_total?: number | null;
+  speed?: number | null;
+  eta_seconds?: number | null;
+  downloaded_bytes?: number | null;
+  total_bytes?: number | null;
 }
 
 interface DownloadTabProps {
@@ -491,7 +495,7 @@ export const DownloadTab: React.FC<DownloadTabProps> = ({ installing, setInstall
               </svg>
               <div>
                 <ImportTitle>导入本地整合包</ImportTitle>
-                <ImportDesc>支持 .zip 和 .mrpack 格式</ImportDesc>
+                <ImportDesc>支持 .zip 和 .mrpack 格式，以及实验性快照 .zip</ImportDesc>
               </div>
             </ImportArea>
             <div style={{ flex: 1, minHeight: 0 }}>
@@ -96,6 +96,27 @@ const PercentText = styled.div`
   margin-bottom: 20px;
 `;
 
+const SpeedText = styled.div`
+  font-size: 12px;
+  color: #64748b;
+  margin: -16px 0 16px;
+  font-variant-numeric: tabular-nums;
+`;
+
+const formatBytes = (bytes: number) => {
+  if (bytes >= 1024 * 1024 * 1024) return `${(bytes / 1024 / 1024 / 1024).toFixed(2)} GB`;
+  if (bytes >= 1024 * 1024) return `${(bytes / 1024 / 1024).toFixed(1)} MB`;
+  return `${(bytes / 1024).toFixed(0)} KB`;
+};
+
+const formatSpeed = (bytesPerSec: number) => `${formatBytes(bytesPerSec)}/s`;
+
+const formatEta = (seconds: number) => {
+  const m = Math.floor(seconds / 60);
+  const s = seconds % 60;
+  return m > 0 ? `${m}分${s}秒` : `${s}秒`;
+};
+
 const LogContainer = styled.div`
   flex: 1;
   background-color: #1e293b;
@@ -127,6 +148,10 @@ interface DownloadProgress {
   current_file_progress?: number | null;
   current_file_downloaded?: number | null;
   current_file_total?: number | null;
+  speed?: number | null;
+  eta_seconds?: number | null;
+  downloaded_bytes?: number | null;
+  total_bytes?: number | null;
 }
 
 interface DownloadFileProgress {
@@ -240,13 +265,14 @@ export function DownloadWindow() {
       const modpackPath = params.get('modpackPath');
       const containerId = params.get('containerId');
       const serverId = params.get('serverId');
+      const serverAddress = params.get('serverAddress');
       const javaPath = params.get('javaPath') || null;
       
       if (!hasStartedRef.current) {
         if (containerId && vid) {
           hasStartedRef.current = true;
           setVersionId(vid);
-          startDeploy(containerId, vid, serverId);
+          startDeploy(containerId, vid, serverId, serverAddress);
         } else if (vid) {
           hasStartedRef.current = true;
           setVersionId(vid);
@@ -269,7 +295,7 @@ export function DownloadWindow() {
     };
   }, []);
 
-  async function startDeploy(containerId: string, vid: string, serverId: string | null) {
+  async function startDeploy(containerId: string, vid: string, serverId: string | null, serverAddress: string | null) {
     const currentLabel = getCurrentWindow().label;
     try {
       setStatus("正在获取部署清单...");
@@ -359,7 +385,9 @@ export function DownloadWindow() {
                await invoke('set_client_server_id', {
                    versionId: vid,
                    serverId: parseInt(serverId),
-                   gamePath: gamePath || null
+                   gamePath: gamePath || null,
+                   serverName: vid,
+                   serverAddress: serverAddress || null
                });
            } catch (e) {
                console.error("Failed to save server ID to client config:", e);
@@ -484,6 +512,14 @@ export function DownloadWindow() {
         {progress && (
           <>
             <PercentText>{progress.percent.toFixed(1)}%</PercentText>
+            {progress.speed != null && (
+              <SpeedText>
+                {progress.downloaded_bytes != null && progress.total_bytes != null &&
+                  `${formatBytes(progress.downloaded_bytes)} / ${formatBytes(progress.total_bytes)} · `}
+                {formatSpeed(progress.speed)}
+                {progress.eta_seconds != null && ` · 剩余 ${formatEta(progress.eta_seconds)}`}
+              </SpeedText>
+            )}
             <ProgressBarContainer>
               <ProgressBarFill $percent={progress.percent} />
             </ProgressBarContainer>
@@ -101,6 +101,19 @@ const Input = styled.input`
   }
 `;
 
+const Select = styled.select`
+  padding: 0.8rem;
+  border: 1px solid var(--border-color);
+  border-radius: 8px;
+  font-size: 1rem;
+  background: white;
+
+  &:focus {
+    outline: none;
+    border-color: var(--accent-color);
+  }
+`;
+
 const RangeInput = styled.input`
   width: 100%;
   appearance: none;
@@ -307,6 +320,20 @@ interface VersionDetails {
   mc_path: string;
 }
 
+interface VersionRuntimeInfo {
+  mc_version?: string;
+  loader_type?: string;
+  loader_version?: string;
+}
+
+interface OptiFineBuild {
+  mcversion: string;
+  type: string;
+  patch: string;
+  filename: string;
+  forge?: string;
+}
+
 interface MemoryInfo {
   total_mb: number;
   available_mb: number;
@@ -317,6 +344,9 @@ export const LaunchSettingsModal: React.FC<LaunchSettingsModalProps> = ({ isOpen
   const [packChoiceOpen, setPackChoiceOpen] = useState(false);
   const packChoiceResolver = React.useRef<((format: 'modrinth' | 'curseforge' | null) => void) | null>(null);
   const [versionDetails, setVersionDetails] = useState<VersionDetails | null>(null);
+  const [optifineBuilds, setOptifineBuilds] = useState<OptiFineBuild[]>([]);
+  const [selectedOptifine, setSelectedOptifine] = useState('');
+  const [isInstallingOptifine, setIsInstallingOptifine] = useState(false);
   const [memoryInfo, setMemoryInfo] = useState<MemoryInfo | null>(null);
   const [hasSavedConfig, setHasSavedConfig] = useState(false);
   const [config, setConfig] = useState<VersionConfig>({
@@ -336,6 +366,45 @@ export const LaunchSettingsModal: React.FC<LaunchSettingsModalProps> = ({ isOpen
     }
   }, [isOpen, versionId]);
 
+  // OptiFine goes into the mods folder of Forge versions, or becomes its own version on top of vanilla
+  useEffect(() => {
+    setOptifineBuilds([]);
+    setSelectedOptifine('');
+    if (!isOpen || !versionId) return;
+    const gamePath = localStorage.getItem('gamePath') || null;
+    invoke<VersionRuntimeInfo>('get_version_runtime_info', { versionId, gamePath })
+      .then(info => {
+        if (!info.mc_version || (info.loader_type !== 'forge' && info.loader_type !== 'vanilla')) return;
+        return invoke<OptiFineBuild[]>('fetch_optifine_versions', { gameVersion: info.mc_version }).then(builds => {
+          setOptifineBuilds(builds);
+          if (builds.length > 0) {
+            setSelectedOptifine(`${builds[0].type}_${builds[0].patch}`);
+          }
+        });
+      })
+      .catch(console.error);
+  }, [isOpen, versionId]);
+
+  const handleInstallOptifine = async () => {
+    if (!selectedOptifine || isInstallingOptifine) return;
+    setIsInstallingOptifine(true);
+    try {
+      const installedVersion = await invoke<string>('install_optifine', {
+        versionId,
+        optifineVersion: selectedOptifine,
+        gamePath: localStorage.getItem('gamePath') || null,
+        javaPath: localStorage.getItem('javaPath') || null,
+        taskId: null
+      });
+      alert(installedVersion === versionId ? 'OptiFine 已安装到 Mods 文件夹' : `OptiFine 已安装为新版本 ${installedVersion}`);
+    } catch (e: any) {
+      console.error(e);
+      alert(`安装 OptiFine 失败: ${e.message || e}`);
+    } finally {
+      setIsInstallingOptifine(false);
+    }
+  };
+
   const handleOpenVersionFolder = async () => {
     if (!versionDetails) return;
     try {
@@ -585,6 +654,27 @@ export const LaunchSettingsModal: React.FC<LaunchSettingsModalProps> = ({ isOpen
                 </svg>
                 打开 Mods 文件夹
               </AdvancedActionButton>
+              {optifineBuilds.length > 0 && (
+                <div style={{ display: 'flex', gap: '0.5rem', alignItems: 'center' }}>
+                  <Select
+                    value={selectedOptifine}
+                    onChange={e => setSelectedOptifine(e.target.value)}
+                    disabled={isInstallingOptifine}
+                  >
+                    {optifineBuilds.map(b => (
+                      <option key={`${b.type}_${b.patch}`} value={`${b.type}_${b.patch}`}>{`${b.type}_${b.patch}`}</option>
+                    ))}
+                  </Select>
+                  <AdvancedActionButton onClick={handleInstallOptifine} disabled={isInstallingOptifine}>
+                    {isInstallingOptifine ? (
+                      <>
+                        <LoadingSpinner size={20} borderWidth={2} />
+                        正在安装...
+                      </>
+                    ) : '安装 OptiFine'}
+                  </AdvancedActionButton>
+                </div>
+              )}
               <AdvancedActionButton onClick={handleDeleteVersion}>
                 <svg viewBox="0 0 24 24" width="20" height="20" fill="currentColor" style={{ marginRight: '0.5rem' }}>
                   <path d="M6 7h12v2H6V7zm2 3h8l-1 9H9l-1-9zm3-6h2l1 2h-4l1-2z"/>
@@ -16,6 +16,7 @@ interface LoginCardProps {
   onLogin: (e: React.FormEvent) => void;
   onRegister: () => void;
   onCAFLogin: () => void;
+  serverName?: string | null;
 }
 
 const LoginContainer = styled.div`
@@ -149,6 +150,15 @@ const ActionButton = styled.button<{ $primary?: boolean }>`
   }
 `;
 
+const ServerName = styled.div`
+  width: 100%;
+  color: rgba(255, 255, 255, 0.8);
+  font-size: 0.85rem;
+  text-align: center;
+  margin-top: -0.5rem;
+  font-family: 'Segoe UI', Tahoma, Geneva, Verdana, sans-serif;
+`;
+
 const ButtonGroup = styled.div`
   display: flex;
   gap: 1rem;
@@ -164,7 +174,8 @@ export const LoginCard: React.FC<LoginCardProps> = ({
   isLoading,
   onLogin,
   onRegister,
-  onCAFLogin
+  onCAFLogin,
+  serverName
 }) => {
   const [activeTab, setActiveTab] = useState<'login' | 'caf'>('caf');
 
@@ -188,6 +199,8 @@ export const LoginCard: React.FC<LoginCardProps> = ({
           CAF 登录
         </TabButton>
       </TabContainer>
+
+      {serverName && <ServerName>认证服务器: {serverName}</ServerName>}
       
       {activeTab === 'login' ? (
         <LoginForm onSubmit={onLogin}>
@@ -1,6 +1,6 @@
 import React, { useState, useEffect } from 'react';
 import styled, { keyframes, css } from 'styled-components';
-import { Server, DockerServer, ServerStatus } from '../types';
+import { Server, DockerServer, ServerStatus, TextSpan } from '../types';
 import { invoke } from '@tauri-apps/api/core';
 import { PlayIcon, StopIcon, CommandLineIcon, ArrowDownTrayIcon, TrashIcon } from '@heroicons/react/24/solid';
 
@@ -302,6 +302,20 @@ const ImportModpackIcon: React.FC = () => (
   </svg>
 );
 
+// Spans come already styled from the backend, which understands both JSON components and § codes
+function renderSpans(spans: TextSpan[]) {
+  return spans.map((span, index) => (
+    <span key={index} style={{
+      color: span.color,
+      fontWeight: span.bold ? 'bold' : undefined,
+      fontStyle: span.italic ? 'italic' : undefined,
+      textDecoration: [span.underlined && 'underline', span.strikethrough && 'line-through'].filter(Boolean).join(' ') || undefined,
+    }}>
+      {span.text}
+    </span>
+  ));
+}
+
 function parseMinecraftColors(text: string) {
   if (!text) return null;
   
@@ -447,6 +461,8 @@ export const ServerCard: React.FC<ServerCardProps> = ({
         ? dockerStatus.description
         : server.version
       : (server as Server).motd || (server as Server).ip_address;
+    const motdSpans = isDocker ? dockerStatus?.description_spans : (server as Server).motd_spans;
+    const motdContent = motdSpans ? renderSpans(motdSpans) : parseMinecraftColors(rawMotd);
 
     const [motdHover, setMotdHover] = React.useState(false);
     const [motdPos, setMotdPos] = React.useState({ x: 0, y: 0 });
@@ -476,10 +492,10 @@ export const ServerCard: React.FC<ServerCardProps> = ({
             onMouseMove={handleMotdMouseMove}
             title=""
           >
-            {parseMinecraftColors(rawMotd)}
+            {motdContent}
           </DetailText>
           <MotdTooltip $visible={motdHover} $x={motdPos.x} $y={motdPos.y}>
-            {parseMinecraftColors(rawMotd)}
+            {motdContent}
           </MotdTooltip>
         </DetailRow>
       </>
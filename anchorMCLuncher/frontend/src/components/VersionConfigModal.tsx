@@ -124,8 +124,10 @@ export function VersionConfigModal({ isOpen, onClose, gameVersion, onConfirm }:
           >
             <option value="vanilla">原版 (Vanilla)</option>
             <option value="fabric">Fabric</option>
+            <option value="quilt">Quilt</option>
             <option value="forge">Forge</option>
             <option value="neoforge">NeoForge</option>
+            <option value="optifine">OptiFine</option>
           </Select>
         </FormGroup>
 
@@ -21,6 +21,7 @@ export interface BackendServer {
 export interface Server extends BackendServer {
   // Frontend specific fields (derived or fetched live)
   motd?: string;
+  motd_spans?: TextSpan[];
   players?: string;
   ping?: number;
   favicon?: string;
@@ -35,11 +36,23 @@ export interface DockerServer {
   version: string;
 }
 
+export interface TextSpan {
+    text: string;
+    color?: string;
+    bold: boolean;
+    italic: boolean;
+    underlined: boolean;
+    strikethrough: boolean;
+    obfuscated: boolean;
+}
+
 export interface ServerStatus {
     description: string;
+    description_spans: TextSpan[];
     players: {
         max: number;
         online: number;
+        sample: { name: string; id: string }[];
     };
     version: {
         name: string;
@@ -47,4 +60,75 @@ export interface ServerStatus {
     };
     favicon?: string;
     latency: number;
+    resolved_host: string;
+    resolved_port: number;
+    ping_protocol: 'modern' | 'legacy_1_6' | 'legacy_1_4' | 'beta';
+    latency_stats?: {
+        min: number;
+        avg: number;
+        max: number;
+        samples: number;
+    };
+    mod_info?: {
+        loader: string;
+        mods: { id: string; version?: string }[];
+        fml_network_version?: number;
+        truncated: boolean;
+    };
+    enforces_secure_chat?: boolean;
+    previews_chat?: boolean;
+}
+
+export interface PingResult {
+    host: string;
+    port?: number;
+    status?: ServerStatus;
+    error?: string;
+    cached: boolean;
+}
+
+export interface LanGame {
+    motd: string;
+    host: string;
+    port: number;
+    last_seen: number;
+}
+
+export interface QueryStatus {
+    motd: string;
+    game_type: string;
+    map: string;
+    num_players: number;
+    max_players: number;
+    host_port: number;
+    host_ip: string;
+    full: boolean;
+    version?: string;
+    server_software?: string;
+    plugins: string[];
+    players: string[];
+    raw: Record<string, string>;
+}
+
+export interface DownloadTaskInfo {
+    id: string;
+    version_id: string;
+    priority: number;
+    status: 'queued' | 'running' | 'paused' | 'completed' | 'failed' | 'cancelled' | 'interrupted';
+    total_files: number;
+    completed_files: number;
+    failed_files: number;
+    created_at: number;
+    error?: string;
+}
+
+export interface AuthServerInfo {
+    api_root: string;
+    server_name?: string;
+    implementation_name?: string;
+    implementation_version?: string;
+    homepage?: string;
+    register_url?: string;
+    skin_domains: string[];
+    features: Record<string, boolean>;
 }